@@ -0,0 +1,210 @@
+//! Range-partitioned Merkle trees for background replica reconciliation.
+//!
+//! Replication on the write path (`ClusterCache::insert`) is a best-effort
+//! broadcast to a key's replica set: a node that was down, or that simply
+//! dropped a `KeyUpdated` message, stays silently divergent from its peers
+//! forever. The anti-entropy loop in `ClusterCache` periodically compares a
+//! `MerkleTree` of this node's keyspace against a peer's, descending only
+//! into the branches whose hashes disagree, so the amount of data exchanged
+//! is proportional to how much the two replicas have actually diverged
+//! rather than to the size of the keyspace.
+
+use crate::cache::CacheEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// Number of leaf ranges the keyspace is partitioned into, by the top bits
+/// of each key's hash. Must be a power of two so every internal node has
+/// exactly two children.
+pub const RANGE_COUNT: usize = 256;
+
+/// Which leaf range a key falls into.
+fn range_for(key: &str) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() >> 56) as usize % RANGE_COUNT
+}
+
+/// Digest used to detect whether two replicas' copies of a key differ.
+/// Hashes the value and tombstone flag rather than `CacheEntry::version`:
+/// divergence detection only needs to know "these differ," not which one is
+/// newer — that ordering is `FastbuCache::insert_entry`'s job, applied once
+/// a differing key has been pulled.
+fn content_digest(entry: &CacheEntry) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    entry.value().hash(&mut hasher);
+    entry.is_tombstone().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A single (key, content digest) pair as it appears in a leaf range.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeEntry {
+    pub key: String,
+    pub digest: u64,
+}
+
+/// A peer's answer to a query about one Merkle tree node: its hash, its
+/// children's hashes if it's an internal node, or its full entry list if
+/// it's a leaf.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerkleNodeAnswer {
+    pub hash: u64,
+    pub children: Option<(u64, u64)>,
+    pub entries: Option<Vec<RangeEntry>>,
+}
+
+fn leaf_hash(entries: &[RangeEntry]) -> u64 {
+    // Sort so the hash doesn't depend on the order entries were collected in.
+    let mut sorted: Vec<&RangeEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| a.key.cmp(&b.key));
+    let mut hasher = DefaultHasher::new();
+    for entry in sorted {
+        entry.key.hash(&mut hasher);
+        entry.digest.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// A binary Merkle tree over `RANGE_COUNT` leaf ranges of a node's keyspace.
+/// `levels[0]` holds the per-range leaf hashes; `levels.last()` is the
+/// single root hash. Rebuilt wholesale from a fresh keyspace snapshot on
+/// each anti-entropy tick rather than maintained incrementally, since the
+/// cadence is seconds-to-minutes, not per-write.
+pub struct MerkleTree {
+    /// `ranges[i]` holds the full (key, digest) list for leaf range `i`, so
+    /// a leaf mismatch can be resolved locally without rescanning the cache.
+    ranges: Vec<Vec<RangeEntry>>,
+    levels: Vec<Vec<u64>>,
+}
+
+impl MerkleTree {
+    /// Build a tree from a snapshot of this node's keyspace.
+    pub fn build(entries: Vec<(String, CacheEntry)>) -> Self {
+        let mut ranges: Vec<Vec<RangeEntry>> = vec![Vec::new(); RANGE_COUNT];
+        for (key, entry) in entries {
+            let digest = content_digest(&entry);
+            ranges[range_for(&key)].push(RangeEntry { key, digest });
+        }
+
+        let leaves: Vec<u64> = ranges.iter().map(|r| leaf_hash(r)).collect();
+        let mut levels = vec![leaves];
+        while levels.last().expect("levels is never empty").len() > 1 {
+            let prev = levels.last().expect("levels is never empty");
+            let next = prev
+                .chunks(2)
+                .map(|pair| {
+                    let mut hasher = DefaultHasher::new();
+                    pair[0].hash(&mut hasher);
+                    pair.get(1).unwrap_or(&pair[0]).hash(&mut hasher);
+                    hasher.finish()
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        MerkleTree { ranges, levels }
+    }
+
+    /// Tree height: 0 is the leaf level, `height()` the root.
+    pub fn height(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn root(&self) -> u64 {
+        self.levels[self.height()][0]
+    }
+
+    /// Hash of the node at (`level`, `index`), or `None` if out of range.
+    pub fn node_hash(&self, level: usize, index: usize) -> Option<u64> {
+        self.levels.get(level)?.get(index).copied()
+    }
+
+    /// Hashes of the two children of the node at (`level`, `index`).
+    /// `None` if `level` is the leaf level (0) or the node doesn't exist.
+    pub fn children(&self, level: usize, index: usize) -> Option<(u64, u64)> {
+        if level == 0 {
+            return None;
+        }
+        let child_level = self.levels.get(level - 1)?;
+        let left = *child_level.get(index * 2)?;
+        let right = child_level.get(index * 2 + 1).copied().unwrap_or(left);
+        Some((left, right))
+    }
+
+    /// Leaf range indices covered by the subtree rooted at (`level`, `index`).
+    pub fn leaf_ranges(&self, level: usize, index: usize) -> Range<usize> {
+        let span = 1usize << level;
+        let start = index * span;
+        start..(start + span).min(RANGE_COUNT)
+    }
+
+    /// The (key, digest) entries held in a single leaf range.
+    pub fn range_entries(&self, range_index: usize) -> &[RangeEntry] {
+        &self.ranges[range_index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<(String, CacheEntry)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), CacheEntry::new(v.to_string(), None)))
+            .collect()
+    }
+
+    #[test]
+    fn identical_keyspaces_produce_identical_roots() {
+        let a = MerkleTree::build(entries(&[("a", "1"), ("b", "2"), ("c", "3")]));
+        let b = MerkleTree::build(entries(&[("c", "3"), ("a", "1"), ("b", "2")]));
+        assert_eq!(a.root(), b.root());
+    }
+
+    #[test]
+    fn diverging_value_changes_the_root() {
+        let a = MerkleTree::build(entries(&[("a", "1")]));
+        let b = MerkleTree::build(entries(&[("a", "2")]));
+        assert_ne!(a.root(), b.root());
+    }
+
+    #[test]
+    fn missing_key_changes_the_root() {
+        let a = MerkleTree::build(entries(&[("a", "1"), ("b", "2")]));
+        let b = MerkleTree::build(entries(&[("a", "1")]));
+        assert_ne!(a.root(), b.root());
+    }
+
+    #[test]
+    fn only_the_affected_branch_mismatches() {
+        let a = MerkleTree::build(entries(&[("a", "1"), ("b", "2")]));
+        let b = MerkleTree::build(entries(&[("a", "1"), ("b", "999")]));
+
+        let range_a = range_for("a");
+        let range_b = range_for("b");
+        if range_a != range_b {
+            assert_eq!(a.node_hash(0, range_a), b.node_hash(0, range_a));
+        }
+        assert_ne!(a.node_hash(0, range_b), b.node_hash(0, range_b));
+    }
+
+    #[test]
+    fn leaf_ranges_cover_the_whole_keyspace_at_the_root() {
+        let tree = MerkleTree::build(entries(&[("a", "1")]));
+        assert_eq!(tree.leaf_ranges(tree.height(), 0), 0..RANGE_COUNT);
+    }
+
+    #[test]
+    fn expired_entries_are_excluded_by_the_caller_not_the_tree() {
+        // MerkleTree trusts its input; filtering expired entries out of a
+        // snapshot is FastbuCache::snapshot_entries's job, not the tree's.
+        let entry = CacheEntry::new("1".to_string(), Some(Duration::from_secs(0)));
+        let tree = MerkleTree::build(vec![("a".to_string(), entry)]);
+        assert_eq!(tree.range_entries(range_for("a")).len(), 1);
+    }
+}
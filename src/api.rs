@@ -1,10 +1,85 @@
-use crate::api_cache_trait::ApiCache;
+use crate::api_cache_trait::{ApiCache, NodeJoinRequest};
 use crate::cache::FastbuCache;
 use log::{debug, error, info, warn};
+use serde::Deserialize;
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use warp::Filter;
 
+/// Query string accepted by `POST /set/{key}/{value}`, e.g. `?ttl=60` to
+/// expire the key 60 seconds from now.
+#[derive(Debug, Deserialize)]
+struct SetQuery {
+    ttl: Option<u64>,
+}
+
+/// How often the background sweep checks for expired entries.
+const PURGE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Bearer-token access control for the HTTP API. An empty token set disables
+/// auth entirely (the default), so existing deployments keep working
+/// unchanged until tokens are configured.
+#[derive(Clone)]
+pub struct AuthConfig {
+    tokens: Arc<HashSet<String>>,
+    /// Whether `GET /get/{key}`, `GET /status`, and `GET /cluster/placement/{key}`
+    /// also require a token. `POST /set/{key}/{value}` and the cluster
+    /// membership endpoints always require one once any token is configured;
+    /// reads are public by default so monitoring/health checks don't need a
+    /// token.
+    pub require_auth_get: bool,
+}
+
+impl AuthConfig {
+    pub fn new(tokens: Vec<String>, require_auth_get: bool) -> Self {
+        AuthConfig {
+            tokens: Arc::new(tokens.into_iter().collect()),
+            require_auth_get,
+        }
+    }
+
+    /// No tokens configured — every route is left open, matching the
+    /// server's behavior before auth existed.
+    pub fn disabled() -> Self {
+        AuthConfig {
+            tokens: Arc::new(HashSet::new()),
+            require_auth_get: false,
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+}
+
+/// Rejection cause for a missing or invalid bearer token, kept distinct from
+/// generic failures so `handle_rejection` can answer 401 instead of 500.
+#[derive(Debug)]
+struct AuthRejection;
+impl warp::reject::Reject for AuthRejection {}
+
+/// Filter that passes through untouched when `auth` has no tokens configured,
+/// and otherwise requires a valid `Authorization: Bearer <token>` header.
+fn require_auth(auth: AuthConfig) -> impl Filter<Extract = ((),), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("authorization").and_then(move |header: Option<String>| {
+        let auth = auth.clone();
+        async move {
+            if !auth.enabled() {
+                return Ok(());
+            }
+            match header.as_deref().and_then(|h| h.strip_prefix("Bearer ")) {
+                Some(token) if auth.tokens.contains(token) => Ok(()),
+                _ => {
+                    warn!("Rejecting request with missing or invalid bearer token");
+                    Err(warp::reject::custom(AuthRejection))
+                }
+            }
+        }
+    })
+}
+
 async fn handle_rejection(
     err: warp::Rejection,
 ) -> Result<impl warp::Reply, std::convert::Infallible> {
@@ -13,6 +88,11 @@ async fn handle_rejection(
             "NOT_FOUND",
             warp::http::StatusCode::NOT_FOUND,
         ))
+    } else if err.find::<AuthRejection>().is_some() {
+        Ok(warp::reply::with_status(
+            "UNAUTHORIZED",
+            warp::http::StatusCode::UNAUTHORIZED,
+        ))
     } else {
         Ok(warp::reply::with_status(
             "INTERNAL_SERVER_ERROR",
@@ -21,18 +101,31 @@ async fn handle_rejection(
     }
 }
 
-pub async fn start_server<T: ApiCache + 'static>(cache: T, host: String, port: u16) -> Result<(), warp::Error> {
+pub async fn start_server<T: ApiCache + 'static>(
+    cache: Arc<T>,
+    host: String,
+    port: u16,
+    auth: AuthConfig,
+) -> Result<(), warp::Error> {
     info!("Initializing server with host: {} and port: {}", host, port);
-    let cache = Arc::new(cache);
+
+    // Reads are public by default; only gated when `require_auth_get` opts in.
+    let get_auth = if auth.require_auth_get {
+        auth.clone()
+    } else {
+        AuthConfig::disabled()
+    };
 
     /*
      * GET /get/{key} - Retrieves the value associated with a given key from the cache.
-     * If the key is not found, returns 404 Not Found.
+     * If the key is not found, returns 404 Not Found. Requires a bearer token
+     * only when `require_auth_get` is set.
      */
     let get_cache = cache.clone();
     let get_item = warp::path!("get" / String)
+        .and(require_auth(get_auth.clone()))
         .and(warp::any().map(move || get_cache.clone()))
-        .and_then(|key: String, cache: Arc<T>| {
+        .and_then(|key: String, _auth: (), cache: Arc<T>| {
             debug!("Received GET request for key: {}", key);
             async move {
                 let value = cache.get(&key).await;
@@ -54,20 +147,25 @@ pub async fn start_server<T: ApiCache + 'static>(cache: T, host: String, port: u
 
     /*
      * POST /set/{key}/{value} - Stores a key-value pair in the cache.
-     * Returns 200 OK upon successful insertion.
+     * Accepts an optional `?ttl=<seconds>` query param; once it elapses the
+     * key behaves as a miss. Requires a bearer token whenever any token is
+     * configured. Returns 200 OK upon successful insertion.
      */
     let set_cache = cache.clone();
     let set_item = warp::path!("set" / String / String)
         .and(warp::post())
+        .and(warp::query::<SetQuery>())
+        .and(require_auth(auth.clone()))
         .and(warp::any().map(move || set_cache.clone()))
-        .and_then(|key: String, value: String, cache: Arc<T>| {
+        .and_then(|key: String, value: String, query: SetQuery, _auth: (), cache: Arc<T>| {
             debug!(
-                "Received POST request to set key: {} with value: {}",
-                key, value
+                "Received POST request to set key: {} with value: {} (ttl: {:?})",
+                key, value, query.ttl
             );
             async move {
-                debug!("Calling cache.set for key: {}", key);
-                match cache.set(key.clone(), value.clone()).await {
+                let ttl = query.ttl.map(Duration::from_secs);
+                debug!("Calling cache.set_with_ttl for key: {}", key);
+                match cache.set_with_ttl(key.clone(), value.clone(), ttl).await {
                     Ok(_) => {
                         debug!("Successfully inserted key: {}", key);
                         // Explicitly return a response
@@ -88,11 +186,135 @@ pub async fn start_server<T: ApiCache + 'static>(cache: T, host: String, port: u
             }
         });
 
+    /*
+     * GET /status - Reports this node's identity and, in cluster mode, the
+     * cluster topology (peers, reachability, slot assignments) and aggregate
+     * key/memory counts, for monitoring and orchestration. Requires a bearer
+     * token only when `require_auth_get` is set, same as `/get` -- this is a
+     * read, but one that leaks full cluster topology, so it follows the read
+     * auth policy rather than being unconditionally public.
+     */
+    let status_cache = cache.clone();
+    let status = warp::path!("status")
+        .and(require_auth(get_auth.clone()))
+        .and(warp::any().map(move || status_cache.clone()))
+        .and_then(|_auth: (), cache: Arc<T>| async move {
+            debug!("Received status request");
+            Ok::<_, warp::Rejection>(warp::reply::json(&cache.status().await))
+        });
+
+    /*
+     * POST /cluster/nodes - Joins a peer to the running cluster without a
+     * restart. Body: {"id", "host", "cluster_port", "api_port"?, "slots"?}.
+     * Requires a bearer token whenever any token is configured, same as
+     * `/set` — this mutates cluster membership, not just cache contents.
+     */
+    let add_node_cache = cache.clone();
+    let add_node = warp::path!("cluster" / "nodes")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(require_auth(auth.clone()))
+        .and(warp::any().map(move || add_node_cache.clone()))
+        .and_then(|req: NodeJoinRequest, _auth: (), cache: Arc<T>| async move {
+            debug!("Received request to add cluster node: {}", req.id);
+            match cache.add_node(req).await {
+                Ok(_) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&"Node added successfully"),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => {
+                    error!("Failed to add cluster node: {}", e);
+                    Ok::<_, warp::Rejection>(warp::reply::with_status(
+                        warp::reply::json(&format!("Failed to add node: {}", e)),
+                        warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ))
+                }
+            }
+        });
+
+    /*
+     * DELETE /cluster/nodes/{id} - Removes a peer from the running
+     * cluster and redistributes its hash slots; data held on the peer is
+     * not copied off it. Requires a bearer
+     * token whenever any token is configured, same as `add_node`.
+     */
+    let remove_node_cache = cache.clone();
+    let remove_node = warp::path!("cluster" / "nodes" / String)
+        .and(warp::delete())
+        .and(require_auth(auth.clone()))
+        .and(warp::any().map(move || remove_node_cache.clone()))
+        .and_then(|id: String, _auth: (), cache: Arc<T>| async move {
+            debug!("Received request to remove cluster node: {}", id);
+            match cache.remove_node(&id).await {
+                Ok(_) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&format!("Node '{}' removed successfully", id)),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => {
+                    error!("Failed to remove cluster node: {}", e);
+                    Ok::<_, warp::Rejection>(warp::reply::with_status(
+                        warp::reply::json(&format!("Failed to remove node '{}': {}", id, e)),
+                        warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ))
+                }
+            }
+        });
+
+    /*
+     * GET /cluster/placement/{key} - Ordered replica set (primary owner
+     * first) a key would route to under the current ring, without
+     * performing a read or write. Empty for a standalone (non-cluster) cache.
+     * Requires a bearer token only when `require_auth_get` is set, same as
+     * `/status` -- this reveals per-key replica placement, not just a read.
+     */
+    let placement_cache = cache.clone();
+    let placement = warp::path!("cluster" / "placement" / String)
+        .and(require_auth(get_auth.clone()))
+        .and(warp::any().map(move || placement_cache.clone()))
+        .and_then(|key: String, _auth: (), cache: Arc<T>| async move {
+            debug!("Received placement request for key: {}", key);
+            Ok::<_, warp::Rejection>(warp::reply::json(&cache.key_placement(&key).await))
+        });
+
     let routes = get_item
         .or(set_item)
+        .or(status)
+        .or(add_node)
+        .or(remove_node)
+        .or(placement);
+
+    /*
+     * GET /metrics - Prometheus text-format exposition of request counters,
+     * hit/miss ratio, key count, and (in cluster mode) per-peer forwarded
+     * request counts and latency. Only registered when built with the
+     * `metrics` feature.
+     */
+    #[cfg(feature = "metrics")]
+    let routes = routes.or(warp::path!("metrics").and_then(|| async move {
+        debug!("Received metrics scrape request");
+        Ok::<_, warp::Rejection>(crate::metrics::gather())
+    }));
+
+    let routes = routes
         .recover(handle_rejection)
         .with(warp::log("fastbu_cache"));
 
+    /*
+     * Background sweep: periodically drop entries whose TTL has elapsed so
+     * they don't linger as dead weight once nothing can reach them anymore.
+     */
+    let purge_cache = cache.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let purged = purge_cache.purge_expired();
+            if purged > 0 {
+                debug!("Background sweep purged {} expired key(s)", purged);
+            }
+        }
+    });
+
     info!("Starting Warp server on {}:{}", host, port);
     let addr: SocketAddr = format!("{}:{}", host, port)
         .parse()
@@ -134,6 +356,30 @@ mod tests {
                 Err(std::io::Error::new(std::io::ErrorKind::Other, "Lock poisoned"))
             }
         }
+
+        async fn del(&self, key: &str) -> Result<bool, std::io::Error> {
+            if let Ok(mut data) = self.data.lock() {
+                Ok(data.remove(key).is_some())
+            } else {
+                Err(std::io::Error::new(std::io::ErrorKind::Other, "Lock poisoned"))
+            }
+        }
+
+        async fn status(&self) -> crate::api_cache_trait::StatusReport {
+            let key_count = self.data.lock().map(|data| data.len()).unwrap_or(0);
+            crate::api_cache_trait::StatusReport {
+                node: crate::api_cache_trait::NodeStatus {
+                    id: "mock".to_string(),
+                    host: None,
+                    api_port: None,
+                    cluster_port: None,
+                },
+                cluster_mode: false,
+                peers: Vec::new(),
+                key_count,
+                approx_memory_bytes: 0,
+            }
+        }
     }
     
     impl MockCache {
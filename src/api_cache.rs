@@ -1,7 +1,9 @@
+use crate::api_cache_trait::{NodeJoinRequest, StatusReport};
 use crate::cache::FastbuCache;
 use crate::cluster_cache::ClusterCache;
 use log::{debug, error, warn};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// API-compatible wrapper around ClusterCache for use in the HTTP API
 pub struct ClusterAwareApiCache {
@@ -21,10 +23,12 @@ impl FastbuCache {
 // Implement methods on ClusterAwareApiCache that match FastbuCache methods,
 // but forward requests to the cluster cache
 impl ClusterAwareApiCache {
-    pub async fn insert(&self, key: String, value: String) -> Result<(), std::io::Error> {
+    pub async fn insert(&self, key: String, value: String, ttl: Option<Duration>) -> Result<(), std::io::Error> {
         debug!("API insert request for key: {}", key);
-        
-        match self.cluster_cache.insert(key.clone(), value).await {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request("set");
+
+        match self.cluster_cache.insert(key.clone(), value, ttl).await {
             Ok(_) => Ok(()),
             Err(e) => {
                 error!("Cluster insert failed for key: {}. Error: {}", key, e);
@@ -35,7 +39,55 @@ impl ClusterAwareApiCache {
     
     pub async fn get(&self, key: &str) -> Option<String> {
         debug!("API get request for key: {}", key);
-        self.cluster_cache.get(key).await
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request("get");
+
+        let result = self.cluster_cache.get(key).await;
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(result.is_some());
+
+        result
+    }
+
+    pub async fn remove(&self, key: &str) -> Result<bool, std::io::Error> {
+        debug!("API remove request for key: {}", key);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request("del");
+
+        match self.cluster_cache.remove(key).await {
+            Ok(removed) => Ok(removed),
+            Err(e) => {
+                error!("Cluster remove failed for key: {}. Error: {}", key, e);
+                Err(std::io::Error::other(format!("Cluster remove failed: {}", e)))
+            }
+        }
+    }
+
+    pub async fn status(&self) -> StatusReport {
+        self.cluster_cache.status().await
+    }
+
+    pub async fn add_node(&self, req: NodeJoinRequest) -> Result<(), std::io::Error> {
+        debug!("API request to add node: {}", req.id);
+
+        self.cluster_cache.add_node(req).await.map_err(|e| {
+            error!("Failed to add node to cluster: {}", e);
+            std::io::Error::other(format!("Failed to add node: {}", e))
+        })
+    }
+
+    pub async fn remove_node(&self, id: &str) -> Result<(), std::io::Error> {
+        debug!("API request to remove node: {}", id);
+
+        self.cluster_cache.remove_node(id).await.map_err(|e| {
+            error!("Failed to remove node from cluster: {}", e);
+            std::io::Error::other(format!("Failed to remove node: {}", e))
+        })
+    }
+
+    pub async fn key_placement(&self, key: &str) -> Vec<String> {
+        self.cluster_cache.replicas_for(key).await
     }
 }
 
@@ -67,7 +119,7 @@ mod tests {
         // Test inserting a value
         let key = "test-key".to_string();
         let value = "test-value".to_string();
-        let result = api_cache.insert(key.clone(), value.clone()).await;
+        let result = api_cache.insert(key.clone(), value.clone(), None).await;
         
         // Should succeed (though actual insertion may be redirected in a real cluster)
         assert!(result.is_ok());
@@ -1,14 +1,117 @@
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::io;
+use std::time::Duration;
+
+/// Body of `POST /cluster/nodes`, describing a peer to join the running cluster
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeJoinRequest {
+    pub id: String,
+    pub host: String,
+    pub cluster_port: u16,
+
+    /// HTTP API port for the joining node; defaults to `cluster_port` if omitted
+    #[serde(default)]
+    pub api_port: Option<u16>,
+
+    /// Hash slot range (inclusive start/end) set on the node as it joins.
+    /// Joining always recomputes slots evenly across all nodes, so this range
+    /// is replaced by the node's share of that split rather than kept.
+    #[serde(default)]
+    pub slots: Option<(u16, u16)>,
+}
+
+/// Identity of the node answering a status request
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeStatus {
+    pub id: String,
+    pub host: Option<String>,
+    pub api_port: Option<u16>,
+    pub cluster_port: Option<u16>,
+}
+
+/// A peer's reachability and slot assignment, as seen by this node
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerStatus {
+    pub id: String,
+    pub host: String,
+    pub api_port: u16,
+    pub cluster_port: u16,
+    pub reachable: bool,
+    pub slots: Option<(u16, u16)>,
+}
+
+/// Machine-readable health/topology snapshot returned by the admin status endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    pub node: NodeStatus,
+    pub cluster_mode: bool,
+    pub peers: Vec<PeerStatus>,
+    pub key_count: usize,
+    pub approx_memory_bytes: usize,
+}
 
 /// Trait for cache implementations that can be used with the API
 #[async_trait]
 pub trait ApiCache: Send + Sync {
     /// Get a value from the cache
     async fn get(&self, key: &str) -> Option<String>;
-    
+
     /// Set a value in the cache
     async fn set(&self, key: String, value: String) -> Result<(), io::Error>;
+
+    /// Set a value with a time-to-live; once `ttl` elapses the key should
+    /// behave as a miss. Defaults to plain `set` (no expiry) for
+    /// implementations that don't support TTLs.
+    async fn set_with_ttl(&self, key: String, value: String, ttl: Option<Duration>) -> Result<(), io::Error> {
+        let _ = ttl;
+        self.set(key, value).await
+    }
+
+    /// Delete a value from the cache, returning whether a key was removed
+    async fn del(&self, key: &str) -> Result<bool, io::Error>;
+
+    /// Check whether a key exists in the cache
+    async fn exists(&self, key: &str) -> bool {
+        self.get(key).await.is_some()
+    }
+
+    /// Report this node's identity and, in cluster mode, the cluster topology
+    /// and peer health, for monitoring/orchestration
+    async fn status(&self) -> StatusReport;
+
+    /// Join a new peer to the cluster at runtime. Standalone caches have no
+    /// cluster to join, so the default rejects the request.
+    async fn add_node(&self, _req: NodeJoinRequest) -> Result<(), io::Error> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "cluster node management is not available in standalone mode",
+        ))
+    }
+
+    /// Remove a peer from the cluster at runtime. Standalone caches have no
+    /// cluster to leave, so the default rejects the request.
+    async fn remove_node(&self, _id: &str) -> Result<(), io::Error> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "cluster node management is not available in standalone mode",
+        ))
+    }
+
+    /// Sweep expired entries from the cache, returning how many were purged.
+    /// Meant to be called periodically from a background task. Implementations
+    /// without TTL support have nothing to sweep, hence the default no-op.
+    fn purge_expired(&self) -> usize {
+        0
+    }
+
+    /// Ordered replica set (primary owner first) that `key` would route to
+    /// under the current placement strategy, without performing a read or
+    /// write. Standalone caches have no cluster topology to place a key
+    /// against, hence the default empty list.
+    async fn key_placement(&self, _key: &str) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// Implement the ApiCache trait for FastbuCache
@@ -17,9 +120,36 @@ impl ApiCache for crate::cache::FastbuCache {
     async fn get(&self, key: &str) -> Option<String> {
         self.get(key)
     }
-    
+
     async fn set(&self, key: String, value: String) -> Result<(), io::Error> {
-        self.insert(key, value).await
+        self.insert(key, value, None).await
+    }
+
+    async fn set_with_ttl(&self, key: String, value: String, ttl: Option<Duration>) -> Result<(), io::Error> {
+        self.insert(key, value, ttl).await
+    }
+
+    async fn del(&self, key: &str) -> Result<bool, io::Error> {
+        self.remove(key).await
+    }
+
+    async fn status(&self) -> StatusReport {
+        StatusReport {
+            node: NodeStatus {
+                id: self.instance_id().to_string(),
+                host: None,
+                api_port: None,
+                cluster_port: None,
+            },
+            cluster_mode: false,
+            peers: Vec::new(),
+            key_count: self.len(),
+            approx_memory_bytes: self.approx_memory_bytes(),
+        }
+    }
+
+    fn purge_expired(&self) -> usize {
+        self.purge_expired()
     }
 }
 
@@ -29,20 +159,44 @@ impl ApiCache for crate::api_cache::ClusterAwareApiCache {
     async fn get(&self, key: &str) -> Option<String> {
         self.get(key).await
     }
-    
+
     async fn set(&self, key: String, value: String) -> Result<(), io::Error> {
-        self.insert(key, value).await
+        self.insert(key, value, None).await
+    }
+
+    async fn set_with_ttl(&self, key: String, value: String, ttl: Option<Duration>) -> Result<(), io::Error> {
+        self.insert(key, value, ttl).await
+    }
+
+    async fn del(&self, key: &str) -> Result<bool, io::Error> {
+        self.remove(key).await
+    }
+
+    async fn status(&self) -> StatusReport {
+        self.status().await
+    }
+
+    async fn add_node(&self, req: NodeJoinRequest) -> Result<(), io::Error> {
+        self.add_node(req).await
+    }
+
+    async fn remove_node(&self, id: &str) -> Result<(), io::Error> {
+        self.remove_node(id).await
+    }
+
+    async fn key_placement(&self, key: &str) -> Vec<String> {
+        self.key_placement(key).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cache::FastbuCache;
+    use crate::cache::{EvictionPolicy, FastbuCache};
 
     #[tokio::test]
     async fn test_fastbu_cache_impl() {
-        let cache = FastbuCache::new();
+        let cache = FastbuCache::new(EvictionPolicy::Lru, usize::MAX);
 
         // Test setting a value
         let key = "test_key".to_string();
@@ -58,7 +212,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_nonexistent_key() {
-        let cache = FastbuCache::new();
+        let cache = FastbuCache::new(EvictionPolicy::Lru, usize::MAX);
 
         // Test getting a nonexistent key
         let get_result = <FastbuCache as ApiCache>::get(&cache, "nonexistent_key").await;
@@ -1,73 +1,525 @@
-use crate::storage::Storage;
+use crate::storage::{FileStorage, StorageBackend};
+use chrono::{DateTime, Utc};
 use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex}; // Add logging and Arc
+use std::time::Duration;
+use tokio::sync::mpsc;
 use tokio::task;
+use uuid::Uuid;
+
+/// Number of in-flight events the observer channel will buffer before
+/// `emit` starts dropping them; observers are meant to be best-effort
+/// (metrics, cache-warming), not a durable log.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Why an entry was evicted, reported alongside `CacheEvent::Evicted`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// The memory tier's byte budget was exceeded and this entry was the
+    /// policy's pick to make room.
+    CapacityExceeded,
+}
+
+/// Activity an observer can subscribe to via `FastbuCache::new_with_events`.
+#[derive(Debug, Clone)]
+pub enum CacheEvent {
+    /// A key was written to the memory tier (and is being persisted to disk).
+    Inserted { key: String },
+    /// A key was pushed out of the memory tier to make room for another.
+    /// It may still be recoverable from disk until that copy also expires.
+    Evicted { key: String, reason: EvictionReason },
+    /// A key's TTL elapsed and it was dropped from memory and/or disk.
+    Expired { key: String },
+    /// A newly-written key was kept out of the memory tier because the
+    /// admission filter judged it colder than the entry it would have had
+    /// to evict; it's still persisted to disk. See `FastbuCache::insert_entry`.
+    AdmissionRejected { key: String },
+}
+
+/// Hybrid logical clock stamp used to order writes to the same key: wall-clock
+/// milliseconds in the high bits of `clock`, a per-key logical counter in the
+/// low bits (so two writes landing in the same millisecond still order
+/// strictly), and the originating node's ID as a final tiebreaker for two
+/// nodes that raced to the same clock value. Deriving `Ord` on the fields in
+/// this order means `>` is exactly the "strictly newer" comparison
+/// replication needs.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct EntryVersion {
+    clock: u64,
+    node_id: String,
+}
+
+impl EntryVersion {
+    /// Bits of `clock` given to the logical counter; the rest holds
+    /// wall-clock milliseconds.
+    const COUNTER_BITS: u32 = 16;
+
+    /// The version older than any version `next` will ever produce. Used as
+    /// the default for entries deserialized from before this field existed,
+    /// so they always lose to a real versioned write.
+    pub fn origin() -> Self {
+        EntryVersion::default()
+    }
+
+    /// Stamp the next version for a write to a key currently at `prior`
+    /// (`None` if the key has no stored version yet). Uses the current wall
+    /// clock if it has advanced past `prior`; otherwise bumps the logical
+    /// counter, which also covers the wall clock going backwards.
+    pub(crate) fn next(node_id: &str, prior: Option<&EntryVersion>) -> Self {
+        let now_clock = (Utc::now().timestamp_millis().max(0) as u64) << Self::COUNTER_BITS;
+        let clock = match prior {
+            Some(prior) if prior.clock >= now_clock => prior.clock + 1,
+            _ => now_clock,
+        };
+        EntryVersion { clock, node_id: node_id.to_string() }
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CacheEntry {
     value: String,
+    /// When this entry should stop being served; `None` means it never
+    /// expires. Checked by both the memory tier (`get`) and the background
+    /// sweep in `FastbuCache::purge_expired`.
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+    /// Logical write version. Compared on every applied write (local or
+    /// replicated) so a late-arriving stale `KeyUpdated` can't clobber a
+    /// newer value; see `FastbuCache::insert_entry`. Defaults to
+    /// `EntryVersion::origin()` for entries persisted before this field
+    /// existed.
+    #[serde(default)]
+    version: EntryVersion,
+    /// A deleted key is kept as a versioned tombstone rather than erased
+    /// outright, so a `KeyUpdated` for the pre-delete value that arrives
+    /// late is recognized as stale (by `version`) and dropped instead of
+    /// resurrecting the key.
+    #[serde(default)]
+    tombstone: bool,
+}
+
+impl CacheEntry {
+    /// Build an entry that expires `ttl` from now, or never if `ttl` is `None`,
+    /// stamped with `EntryVersion::origin()`. Most callers that write through
+    /// `FastbuCache` get a real version from `insert`/`remove` instead; this
+    /// is for call sites (tests, other cache backends) that don't participate
+    /// in replication and so have no stored prior version to compare against.
+    pub fn new(value: String, ttl: Option<Duration>) -> Self {
+        let expires_at = ttl
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+            .map(|d| Utc::now() + d);
+        CacheEntry { value, expires_at, version: EntryVersion::origin(), tombstone: false }
+    }
+
+    /// Like `new`, but stamped with an explicit version and tombstone flag.
+    /// Used by `FastbuCache` to apply a write (local or replicated) that
+    /// must carry a real version rather than the unversioned placeholder.
+    pub(crate) fn with_version(value: String, ttl: Option<Duration>, version: EntryVersion, tombstone: bool) -> Self {
+        let expires_at = ttl
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+            .map(|d| Utc::now() + d);
+        CacheEntry { value, expires_at, version, tombstone }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub(crate) fn version(&self) -> &EntryVersion {
+        &self.version
+    }
+
+    pub(crate) fn is_tombstone(&self) -> bool {
+        self.tombstone
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Which eviction policy the bounded in-memory cache tier uses once it
+/// grows past its configured `max_bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    #[default]
+    Lru,
+    Lfu,
+}
+
+/// Adapter over an in-memory key/value cache that the memory tier can evict
+/// from without knowing which replacement policy it's backed by. Exists so
+/// `CacheData` can hold a `Box<dyn InternalMemoryCache>` and swap LRU for LFU
+/// (or another policy later) behind one field.
+pub trait InternalMemoryCache: Send + Sync {
+    /// Construct an instance with no policy-enforced capacity of its own;
+    /// eviction is driven entirely by `CacheData`'s own byte budget instead.
+    /// Takes no `&self`, so it can't be part of a `dyn` call — exempted from
+    /// the object-safety requirement with `where Self: Sized`.
+    fn unbounded() -> Self
+    where
+        Self: Sized;
+    fn get(&mut self, k: &str) -> Option<&CacheEntry>;
+    fn push(&mut self, k: String, v: CacheEntry);
+    fn pop(&mut self) -> Option<(String, CacheEntry)>;
+    fn remove(&mut self, k: &str) -> Option<CacheEntry>;
+    /// All keys currently held, for the background expiration sweep (the
+    /// eviction policies don't otherwise expose iteration).
+    fn keys(&self) -> Vec<String>;
+    /// The key `pop` would evict next, without evicting it. Used by the
+    /// TinyLFU-style admission filter to compare a would-be victim's access
+    /// frequency against an incoming key's before committing to the swap.
+    /// `None` both when the cache is empty and when a policy (like LFU,
+    /// which is already frequency-based) has no separate notion of an
+    /// admission-filtered victim to weigh.
+    fn peek_victim(&self) -> Option<&str>;
+}
+
+/// Evicts the least-recently-used entry first.
+struct LruMemoryCache(lru::LruCache<String, CacheEntry>);
+
+impl InternalMemoryCache for LruMemoryCache {
+    fn unbounded() -> Self {
+        LruMemoryCache(lru::LruCache::unbounded())
+    }
+
+    fn get(&mut self, k: &str) -> Option<&CacheEntry> {
+        self.0.get(k)
+    }
+
+    fn push(&mut self, k: String, v: CacheEntry) {
+        self.0.push(k, v);
+    }
+
+    fn pop(&mut self) -> Option<(String, CacheEntry)> {
+        self.0.pop_lru()
+    }
+
+    fn remove(&mut self, k: &str) -> Option<CacheEntry> {
+        self.0.pop(k)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.0.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    fn peek_victim(&self) -> Option<&str> {
+        self.0.peek_lru().map(|(k, _)| k.as_str())
+    }
+}
+
+/// Evicts the least-frequently-used entry first.
+struct LfuMemoryCache(lfu_cache::LfuCache<String, CacheEntry>);
+
+impl InternalMemoryCache for LfuMemoryCache {
+    fn unbounded() -> Self {
+        LfuMemoryCache(lfu_cache::LfuCache::new())
+    }
+
+    fn get(&mut self, k: &str) -> Option<&CacheEntry> {
+        self.0.get(k)
+    }
+
+    fn push(&mut self, k: String, v: CacheEntry) {
+        self.0.insert(k, v);
+    }
+
+    fn pop(&mut self) -> Option<(String, CacheEntry)> {
+        self.0.pop_lfu_key_value()
+    }
+
+    fn remove(&mut self, k: &str) -> Option<CacheEntry> {
+        self.0.remove(k)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.0.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    fn peek_victim(&self) -> Option<&str> {
+        // LFU is already frequency-based, so there's no separate admission
+        // decision to make on top of it the way there is for LRU's tail.
+        None
+    }
+}
+
+fn new_memory_cache(policy: EvictionPolicy) -> Box<dyn InternalMemoryCache> {
+    match policy {
+        EvictionPolicy::Lru => Box::new(LruMemoryCache::unbounded()),
+        EvictionPolicy::Lfu => Box::new(LfuMemoryCache::unbounded()),
+    }
+}
+
+/// Number of independent hash rows in `FrequencySketch`. Four rows, each
+/// seeded differently, make it unlikely for a collision in one row to also
+/// collide in the others, which is what lets the count-*min* (minimum across
+/// rows) give a reasonable frequency estimate despite the fixed-size table.
+const SKETCH_DEPTH: usize = 4;
+
+/// Counters per row. Plain `u8` saturating counters rather than the 4-bit
+/// packed counters classic TinyLFU implementations use — this table is tiny
+/// either way, and the simpler representation is easier to follow.
+const SKETCH_WIDTH: usize = 1024;
+
+/// Number of recorded accesses (`record` calls, each bumping one counter per
+/// row) after which every counter is halved, so
+/// the sketch reflects recent access patterns instead of a whole cache
+/// lifetime's worth of history always favoring whatever was hot first.
+const SKETCH_RESET_THRESHOLD: u64 = (SKETCH_WIDTH * SKETCH_DEPTH * 8) as u64;
+
+/// Count-min sketch estimating how often each key has been accessed
+/// recently. Backs the LRU tier's TinyLFU-style admission filter: a newly
+/// inserted key only displaces the current LRU tail if its estimated
+/// frequency is higher than the tail's, which resists a burst of one-hit
+/// wonders evicting entries that are still getting steady traffic.
+struct FrequencySketch {
+    rows: [[u8; SKETCH_WIDTH]; SKETCH_DEPTH],
+    additions_since_reset: u64,
+}
+
+impl FrequencySketch {
+    fn new() -> Self {
+        FrequencySketch {
+            rows: [[0u8; SKETCH_WIDTH]; SKETCH_DEPTH],
+            additions_since_reset: 0,
+        }
+    }
+
+    fn slot(row: usize, key: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        row.hash(&mut hasher);
+        key.hash(&mut hasher);
+        (hasher.finish() as usize) % SKETCH_WIDTH
+    }
+
+    /// Record an access to `key`, aging the whole sketch first if enough
+    /// increments have accumulated since the last reset.
+    fn record(&mut self, key: &str) {
+        if self.additions_since_reset >= SKETCH_RESET_THRESHOLD {
+            for row in &mut self.rows {
+                for counter in row.iter_mut() {
+                    *counter /= 2;
+                }
+            }
+            self.additions_since_reset = 0;
+        }
+
+        for (row, counters) in self.rows.iter_mut().enumerate() {
+            let slot = Self::slot(row, key);
+            counters[slot] = counters[slot].saturating_add(1);
+        }
+        self.additions_since_reset += 1;
+    }
+
+    /// Estimated recent access frequency of `key`: the minimum across rows,
+    /// since a collision can only inflate a row's count, never deflate it.
+    fn estimate(&self, key: &str) -> u8 {
+        (0..SKETCH_DEPTH)
+            .map(|row| self.rows[row][Self::slot(row, key)])
+            .min()
+            .unwrap_or(0)
+    }
 }
 
 pub struct FastbuCache {
     data: Arc<Mutex<CacheData>>,
+    /// Identifies this cache instance in status reports; standalone mode has
+    /// no cluster `Node`, so this stands in for a node ID.
+    instance_id: String,
 }
 
 impl Clone for FastbuCache {
     fn clone(&self) -> Self {
         FastbuCache {
             data: Arc::clone(&self.data),
+            instance_id: self.instance_id.clone(),
         }
     }
 }
 
 struct CacheData {
-    cache: HashMap<String, CacheEntry>,
-    storage: Storage,
+    cache: Box<dyn InternalMemoryCache>,
+    /// Number of entries currently held in the memory tier; the eviction
+    /// trait has no len/iterator of its own, so this is tracked alongside it.
+    len: usize,
+    /// Approximate size (key + value bytes) of everything currently in the
+    /// memory tier. Updated incrementally on push/pop rather than recomputed,
+    /// so it stays an estimate in the same spirit as `approx_memory_bytes`.
+    current_bytes: usize,
+    /// Memory tier budget; once `current_bytes` exceeds this, `insert` evicts
+    /// via `cache.pop()` until back under budget. Evicted entries stay on
+    /// disk in `storage`, so `get` can still serve them via reload.
+    max_bytes: usize,
+    storage: Box<dyn StorageBackend>,
+    /// Observer channel, populated only via `FastbuCache::new_with_events`.
+    /// Sends are best-effort (`try_send`) so a slow or absent observer never
+    /// blocks the cache hot path.
+    events: Option<mpsc::Sender<CacheEvent>>,
+    /// Recent-access frequency estimates backing the LRU tier's admission
+    /// filter; see `FrequencySketch`.
+    frequency: FrequencySketch,
+}
+
+impl CacheData {
+    fn entry_size(key: &str, entry: &CacheEntry) -> usize {
+        key.len() + entry.value().len()
+    }
+
+    /// Evict entries until back under `max_bytes`.
+    fn evict_over_budget(&mut self) {
+        while self.current_bytes > self.max_bytes {
+            match self.cache.pop() {
+                Some((evicted_key, evicted_entry)) => {
+                    self.current_bytes = self
+                        .current_bytes
+                        .saturating_sub(Self::entry_size(&evicted_key, &evicted_entry));
+                    self.len = self.len.saturating_sub(1);
+                    debug!("Evicted key: {} from memory tier (over budget)", evicted_key);
+                    self.emit(CacheEvent::Evicted {
+                        key: evicted_key,
+                        reason: EvictionReason::CapacityExceeded,
+                    });
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Best-effort send to the observer channel, if one is attached. A full
+    /// channel (observer not keeping up) just drops the event rather than
+    /// blocking the caller.
+    fn emit(&self, event: CacheEvent) {
+        if let Some(tx) = &self.events {
+            if let Err(e) = tx.try_send(event) {
+                debug!("Dropping cache event, observer channel unavailable: {}", e);
+            }
+        }
+    }
 }
 
 impl FastbuCache {
-    pub fn new() -> Self {
+    /// `max_bytes` bounds the in-memory tier; pass `usize::MAX` for
+    /// effectively-unbounded behavior (the default when no capacity is
+    /// configured).
+    pub fn new(policy: EvictionPolicy, max_bytes: usize) -> Self {
+        Self::with_events(policy, max_bytes, None)
+    }
+
+    /// Like `new`, but also returns a `Receiver` that observes every insert,
+    /// eviction, and expiration as it happens — for metrics exporters,
+    /// cache-warming, or cluster invalidation fan-out that would otherwise
+    /// have to poll.
+    pub fn new_with_events(policy: EvictionPolicy, max_bytes: usize) -> (Self, mpsc::Receiver<CacheEvent>) {
+        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        (Self::with_events(policy, max_bytes, Some(tx)), rx)
+    }
+
+    /// Like `new`, but persists through a caller-supplied disk tier (e.g.
+    /// `RocksDbStorage`) instead of the default append-only `FileStorage`.
+    pub fn with_storage(policy: EvictionPolicy, max_bytes: usize, storage: Box<dyn StorageBackend>) -> Self {
+        Self::build(policy, max_bytes, storage, None)
+    }
+
+    fn with_events(policy: EvictionPolicy, max_bytes: usize, events: Option<mpsc::Sender<CacheEvent>>) -> Self {
+        let storage: Box<dyn StorageBackend> = Box::new(FileStorage::new().unwrap());
+        Self::build(policy, max_bytes, storage, events)
+    }
+
+    fn build(
+        policy: EvictionPolicy,
+        max_bytes: usize,
+        storage: Box<dyn StorageBackend>,
+        events: Option<mpsc::Sender<CacheEvent>>,
+    ) -> Self {
         FastbuCache {
             data: Arc::new(Mutex::new(CacheData {
-                cache: HashMap::new(),
-                storage: Storage::new().unwrap(),
+                cache: new_memory_cache(policy),
+                len: 0,
+                current_bytes: 0,
+                max_bytes,
+                storage,
+                events,
+                frequency: FrequencySketch::new(),
             })),
+            instance_id: Uuid::new_v4().to_string(),
         }
     }
 
-    pub async fn insert(&self, key: String, value: String) -> Result<(), std::io::Error> {
-        debug!("Attempting to insert key: {} with value: {}", key, value);
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Number of keys currently held in memory
+    pub fn len(&self) -> usize {
+        self.data.lock().unwrap().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Rough estimate of memory used by cached entries (key + value bytes),
+    /// not including allocator/cache-structure overhead
+    pub fn approx_memory_bytes(&self) -> usize {
+        self.data.lock().unwrap().current_bytes
+    }
+
+    pub async fn insert(&self, key: String, value: String, ttl: Option<Duration>) -> Result<(), std::io::Error> {
+        debug!("Attempting to insert key: {} with value: {} (ttl: {:?})", key, value, ttl);
+        let instance_id = self.instance_id.clone();
+        self.insert_local(key, move |prior| {
+            let version = EntryVersion::next(&instance_id, prior);
+            CacheEntry::with_version(value, ttl, version, false)
+        }).await
+    }
+
+    /// Like `insert`, but takes an already-built `CacheEntry` rather than
+    /// computing a fresh `expires_at` from a relative TTL. Used when applying
+    /// a replicated write from a peer, whose entry already carries the
+    /// correct absolute expiry and shouldn't have its clock reset.
+    ///
+    /// Last-writer-wins: `entry` is only applied if its version is strictly
+    /// greater than whatever is currently stored for `key` (including a
+    /// tombstone), so a stale replicated write or a delayed anti-entropy
+    /// pull can't clobber a newer value.
+    pub(crate) async fn insert_entry(&self, key: String, entry: CacheEntry) -> Result<(), std::io::Error> {
+        self.insert_local(key, move |_prior| entry).await
+    }
+
+    /// Apply a write to `key`: `build` is handed the version currently stored
+    /// for the key (`None` if it has never been written) and returns the
+    /// entry to write. `insert`/`remove` stamp a fresh version from it;
+    /// `insert_entry` ignores it and passes a replicated entry through, which
+    /// is then kept only if newer than the stored version. Crucially, `build` runs inside the same critical section as the
+    /// compare-and-write below it, not a separate lock acquisition before
+    /// it — otherwise two concurrent writes to the same key can both read
+    /// the same prior version, mint colliding `EntryVersion`s, and the
+    /// second to land would be silently dropped as "not newer" despite its
+    /// caller having no way to know that.
+    async fn insert_local(
+        &self,
+        key: String,
+        build: impl FnOnce(Option<&EntryVersion>) -> CacheEntry,
+    ) -> Result<(), std::io::Error> {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request("set");
 
-        let entry = CacheEntry {
-            value: value.clone(),
+        let entry = match self.apply_locked(&key, build)? {
+            Some(entry) => entry,
+            None => return Ok(()),
         };
-        
+
         // Create clones for the spawn_blocking operation
         let key_clone = key.clone();
         let entry_clone = entry.clone();
-        
-        {
-            // Update in-memory cache - acquire lock in this smaller scope
-            let mut data = match self.data.lock() {
-                Ok(lock) => lock,
-                Err(e) => {
-                    error!("Failed to acquire lock on data: {}", e);
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        "Lock poisoned",
-                    ));
-                }
-            };
-            
-            data.cache.insert(key.clone(), entry.clone());
-            debug!("In-memory cache updated for key: {}", key);
-        }
-        
+
         // Clone the self reference to move into spawn_blocking
         let self_clone = self.clone();
-        
+
         // Persist to disk using spawn_blocking to avoid blocking the async runtime
         debug!("Attempting to persist key: {} to disk", key);
         let result = task::spawn_blocking(move || {
@@ -85,18 +537,280 @@ impl FastbuCache {
             error!("Task join error when persisting key: {}. Error: {}", key, e);
             Err(std::io::Error::new(std::io::ErrorKind::Other, e))
         });
-        
+
         if result.is_ok() {
             info!("Successfully persisted key:   {} to disk", key);
         } else {
             error!("Failed to persist key: {} to disk", key);
         }
+        #[cfg(feature = "metrics")]
+        crate::metrics::set_key_count(self.len());
         result
     }
 
-    pub fn get(&self, key: &str) -> Option<String> {
+    /// Build and, if it's newer than whatever is currently stored, apply the
+    /// entry for `key` to the in-memory tier — all under a single lock
+    /// acquisition so the read of the prior version and the compare-and-write
+    /// against it are atomic. Returns the entry that was written, or `None`
+    /// if `build`'s result lost to a version already stored (the caller
+    /// should treat that as a no-op, not an error).
+    fn apply_locked(
+        &self,
+        key: &str,
+        build: impl FnOnce(Option<&EntryVersion>) -> CacheEntry,
+    ) -> Result<Option<CacheEntry>, std::io::Error> {
+        let mut data = match self.data.lock() {
+            Ok(lock) => lock,
+            Err(e) => {
+                error!("Failed to acquire lock on data: {}", e);
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Lock poisoned",
+                ));
+            }
+        };
+
+        let stored_version = data
+            .cache
+            .get(key)
+            .map(|e| e.version().clone())
+            .or_else(|| match data.storage.load(key) {
+                Ok(Some(e)) => Some(e.version().clone()),
+                _ => None,
+            });
+
+        let entry = build(stored_version.as_ref());
+        if stored_version.is_some_and(|stored| entry.version() <= &stored) {
+            debug!("Dropping stale write for key: {} (version not newer than stored)", key);
+            return Ok(None);
+        }
+
+        let entry_size = CacheData::entry_size(key, &entry);
+        data.frequency.record(key);
+
+        // An overwrite replaces the resident copy rather than adding a second
+        // entry, so drop it (and its share of `len`/`current_bytes`) first.
+        if let Some(old) = data.cache.remove(key) {
+            let old_size = CacheData::entry_size(key, &old);
+            data.current_bytes = data.current_bytes.saturating_sub(old_size);
+            data.len = data.len.saturating_sub(1);
+        }
+
+        // Only consult the admission filter when this insert would
+        // actually need to evict something to make room; with budget to
+        // spare there's no victim to weigh the new key against.
+        let would_exceed_budget = data.current_bytes.saturating_add(entry_size) > data.max_bytes;
+        let victim = if would_exceed_budget { data.cache.peek_victim() } else { None };
+        let admit = match victim {
+            Some(victim) if data.frequency.estimate(victim) > data.frequency.estimate(key) => false,
+            _ => true,
+        };
+
+        if admit {
+            data.cache.push(key.to_string(), entry.clone());
+            data.len += 1;
+            data.current_bytes = data.current_bytes.saturating_add(entry_size);
+            data.emit(CacheEvent::Inserted { key: key.to_string() });
+            data.evict_over_budget();
+            debug!("In-memory cache updated for key: {}", key);
+        } else {
+            debug!(
+                "Key: {} rejected by the admission filter (colder than the eviction candidate), persisting to disk only",
+                key
+            );
+            data.emit(CacheEvent::AdmissionRejected { key: key.to_string() });
+        }
+
+        Ok(Some(entry))
+    }
+
+    /// Every key currently persisted (including entries evicted from the
+    /// memory tier but still on disk) together with its entry, skipping
+    /// anything already expired. Used by anti-entropy reconciliation, which
+    /// needs to see this node's whole keyspace rather than just the
+    /// memory-tier working set.
+    pub(crate) fn snapshot_entries(&self) -> Vec<(String, CacheEntry)> {
         let data = self.data.lock().unwrap();
-        data.cache.get(key).map(|entry| entry.value.clone())
+        let now = Utc::now();
+        data.storage
+            .keys()
+            .into_iter()
+            .filter_map(|key| match data.storage.load(&key) {
+                Ok(Some(entry)) if !entry.is_expired(now) => Some((key, entry)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Like `get`, but returns the full `CacheEntry` (preserving its expiry)
+    /// instead of just its value. Used by anti-entropy, which needs to push
+    /// an entry to a peer without silently turning it into a non-expiring
+    /// copy.
+    pub(crate) fn get_entry(&self, key: &str) -> Option<CacheEntry> {
+        let mut data = self.data.lock().unwrap();
+        let now = Utc::now();
+
+        if let Some(entry) = data.cache.get(key) {
+            if !entry.is_expired(now) {
+                return Some(entry.clone());
+            }
+        }
+
+        match data.storage.load(key) {
+            Ok(Some(entry)) if !entry.is_expired(now) => Some(entry),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request("get");
+
+        let mut data = self.data.lock().unwrap();
+        let now = Utc::now();
+        data.frequency.record(key);
+
+        let result = match data.cache.get(key) {
+            Some(entry) if entry.is_expired(now) => {
+                debug!("Key: {} found in memory but expired, treating as a miss", key);
+                if let Some(entry) = data.cache.remove(key) {
+                    let entry_size = CacheData::entry_size(key, &entry);
+                    data.current_bytes = data.current_bytes.saturating_sub(entry_size);
+                    data.len = data.len.saturating_sub(1);
+                }
+                if let Err(e) = data.storage.delete(key) {
+                    error!("Failed to delete expired key: {} from disk: {}", key, e);
+                }
+                data.emit(CacheEvent::Expired { key: key.to_string() });
+                None
+            }
+            Some(entry) if entry.is_tombstone() => {
+                debug!("Key: {} is tombstoned, treating as a miss", key);
+                None
+            }
+            Some(entry) => Some(entry.value().to_string()),
+            None => {
+                // Not in the memory tier (never inserted, or evicted under
+                // budget pressure) — fall back to the persisted copy and
+                // repopulate the memory tier with it.
+                match data.storage.load(key) {
+                    Ok(Some(entry)) if entry.is_expired(now) => {
+                        debug!("Key: {} found on disk but expired, dropping it", key);
+                        if let Err(e) = data.storage.delete(key) {
+                            error!("Failed to delete expired key: {} from disk: {}", key, e);
+                        }
+                        data.emit(CacheEvent::Expired { key: key.to_string() });
+                        None
+                    }
+                    Ok(Some(entry)) if entry.is_tombstone() => {
+                        debug!("Key: {} found on disk but tombstoned, treating as a miss", key);
+                        let entry_size = CacheData::entry_size(key, &entry);
+                        data.cache.push(key.to_string(), entry);
+                        data.len += 1;
+                        data.current_bytes = data.current_bytes.saturating_add(entry_size);
+                        data.evict_over_budget();
+                        None
+                    }
+                    Ok(Some(entry)) => {
+                        debug!("Memory-tier miss for key: {}, reloaded from disk", key);
+                        let entry_size = CacheData::entry_size(key, &entry);
+                        let value = entry.value().to_string();
+                        data.cache.push(key.to_string(), entry);
+                        data.len += 1;
+                        data.current_bytes = data.current_bytes.saturating_add(entry_size);
+                        data.evict_over_budget();
+                        Some(value)
+                    }
+                    Ok(None) => None,
+                    Err(e) => {
+                        error!("Failed to reload key: {} from disk: {}", key, e);
+                        None
+                    }
+                }
+            }
+        };
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_lookup(result.is_some());
+
+        result
+    }
+
+    /// Delete `key`. Rather than erasing it outright, this writes a versioned
+    /// tombstone through the same last-writer-wins path as `insert`, so a
+    /// `KeyUpdated` for the pre-delete value that arrives late is recognized
+    /// as stale and dropped instead of resurrecting the key.
+    pub async fn remove(&self, key: &str) -> Result<bool, std::io::Error> {
+        debug!("Attempting to remove key: {}", key);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request("del");
+
+        let existed = self.get(key).is_some();
+
+        let instance_id = self.instance_id.clone();
+        self.insert_local(key.to_string(), move |prior| {
+            let version = EntryVersion::next(&instance_id, prior);
+            CacheEntry::with_version(String::new(), None, version, true)
+        }).await?;
+
+        if existed {
+            info!("Removed key: {} from cache", key);
+        } else {
+            debug!("Key: {} was not present, nothing to remove", key);
+        }
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::set_key_count(self.len());
+
+        Ok(existed)
+    }
+
+    /// Scan both the memory tier and the on-disk index for entries whose TTL
+    /// has elapsed and drop them from both, so a key set with a short TTL
+    /// doesn't linger as dead weight once it's no longer servable. Returns
+    /// the number of entries purged. Meant to be called periodically from a
+    /// background task (see `start_server`), not on the request hot path.
+    pub fn purge_expired(&self) -> usize {
+        let mut data = self.data.lock().unwrap();
+        let now = Utc::now();
+        let mut purged = 0;
+
+        for key in data.cache.keys() {
+            let expired = data.cache.get(&key).is_some_and(|e| e.is_expired(now));
+            if !expired {
+                continue;
+            }
+            if let Some(entry) = data.cache.remove(&key) {
+                let entry_size = CacheData::entry_size(&key, &entry);
+                data.current_bytes = data.current_bytes.saturating_sub(entry_size);
+                data.len = data.len.saturating_sub(1);
+            }
+            if let Err(e) = data.storage.delete(&key) {
+                error!("Failed to delete expired key: {} from disk: {}", key, e);
+            }
+            data.emit(CacheEvent::Expired { key: key.clone() });
+            purged += 1;
+        }
+
+        // Entries evicted out of the memory tier under budget pressure are
+        // only visible through the on-disk index, so sweep that too.
+        for key in data.storage.keys() {
+            match data.storage.load(&key) {
+                Ok(Some(entry)) if entry.is_expired(now) => {
+                    if let Err(e) = data.storage.delete(&key) {
+                        error!("Failed to delete expired key: {} from disk: {}", key, e);
+                    }
+                    data.emit(CacheEvent::Expired { key: key.clone() });
+                    purged += 1;
+                }
+                _ => {}
+            }
+        }
+
+        if purged > 0 {
+            info!("Purged {} expired key(s)", purged);
+        }
+        purged
     }
 }
 
@@ -106,13 +820,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_insert_and_get() {
-        let cache = FastbuCache::new();
+        let cache = FastbuCache::new(EvictionPolicy::Lru, usize::MAX);
 
         let key = "test_key".to_string();
         let value = "test_value".to_string();
 
         // Insert the key-value pair
-        assert!(cache.insert(key.clone(), value.clone()).await.is_ok());
+        assert!(cache.insert(key.clone(), value.clone(), None).await.is_ok());
 
         // Retrieve the value
         let retrieved_value = cache.get(&key);
@@ -122,7 +836,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_nonexistent_key() {
-        let cache = FastbuCache::new();
+        let cache = FastbuCache::new(EvictionPolicy::Lru, usize::MAX);
 
         let key = "nonexistent_key";
 
@@ -130,4 +844,136 @@ mod tests {
         let retrieved_value = cache.get(key);
         assert!(retrieved_value.is_none());
     }
+
+    #[tokio::test]
+    async fn test_eviction_falls_back_to_disk() {
+        // A tiny budget so the second insert evicts the first from memory:
+        // "a"+"first" is 6 bytes and "b"+"second" is 7, so either fits alone
+        // but not both.
+        let cache = FastbuCache::new(EvictionPolicy::Lru, 7);
+
+        cache.insert("a".to_string(), "first".to_string(), None).await.unwrap();
+        cache.insert("b".to_string(), "second".to_string(), None).await.unwrap();
+
+        // "a" was pushed out of the memory tier, but is still retrievable
+        // because it's persisted in the disk tier.
+        assert_eq!(cache.get("a"), Some("first".to_string()));
+        assert_eq!(cache.get("b"), Some("second".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_lfu_policy_insert_and_get() {
+        let cache = FastbuCache::new(EvictionPolicy::Lfu, usize::MAX);
+
+        cache.insert("k".to_string(), "v".to_string(), None).await.unwrap();
+        assert_eq!(cache.get("k"), Some("v".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_treated_as_a_miss() {
+        let cache = FastbuCache::new(EvictionPolicy::Lru, usize::MAX);
+
+        cache
+            .insert("k".to_string(), "v".to_string(), Some(Duration::from_millis(10)))
+            .await
+            .unwrap();
+        assert_eq!(cache.get("k"), Some("v".to_string()));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(cache.get("k"), None);
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_removes_stale_entries() {
+        let cache = FastbuCache::new(EvictionPolicy::Lru, usize::MAX);
+
+        cache
+            .insert("expires".to_string(), "v".to_string(), Some(Duration::from_millis(10)))
+            .await
+            .unwrap();
+        cache.insert("stays".to_string(), "v".to_string(), None).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        assert_eq!(cache.purge_expired(), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_insert_emits_event() {
+        let (cache, mut events) = FastbuCache::new_with_events(EvictionPolicy::Lru, usize::MAX);
+
+        cache.insert("k".to_string(), "v".to_string(), None).await.unwrap();
+
+        match events.recv().await {
+            Some(CacheEvent::Inserted { key }) => assert_eq!(key, "k"),
+            other => panic!("expected Inserted event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_eviction_emits_event() {
+        // Room for "a"+"first" (6 bytes) or "b"+"second" (7), not both.
+        let (cache, mut events) = FastbuCache::new_with_events(EvictionPolicy::Lru, 7);
+
+        cache.insert("a".to_string(), "first".to_string(), None).await.unwrap();
+        cache.insert("b".to_string(), "second".to_string(), None).await.unwrap();
+
+        // First event is "a" being inserted, second is "b" being inserted,
+        // third is "a" being evicted to make room for "b".
+        assert!(matches!(events.recv().await, Some(CacheEvent::Inserted { .. })));
+        assert!(matches!(events.recv().await, Some(CacheEvent::Inserted { .. })));
+        match events.recv().await {
+            Some(CacheEvent::Evicted { key, reason }) => {
+                assert_eq!(key, "a");
+                assert_eq!(reason, EvictionReason::CapacityExceeded);
+            }
+            other => panic!("expected Evicted event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_emits_event() {
+        let (cache, mut events) = FastbuCache::new_with_events(EvictionPolicy::Lru, usize::MAX);
+
+        cache
+            .insert("k".to_string(), "v".to_string(), Some(Duration::from_millis(10)))
+            .await
+            .unwrap();
+        assert!(matches!(events.recv().await, Some(CacheEvent::Inserted { .. })));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(cache.purge_expired(), 1);
+
+        match events.recv().await {
+            Some(CacheEvent::Expired { key }) => assert_eq!(key, "k"),
+            other => panic!("expected Expired event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admission_filter_rejects_a_colder_key_than_the_eviction_candidate() {
+        // A tiny budget so any second entry needs to evict the first:
+        // "hot"+"v" is 4 bytes and "cold"+"v" is 5, so either fits alone but
+        // not both.
+        let (cache, mut events) = FastbuCache::new_with_events(EvictionPolicy::Lru, 5);
+
+        cache.insert("hot".to_string(), "v".to_string(), None).await.unwrap();
+        assert!(matches!(events.recv().await, Some(CacheEvent::Inserted { .. })));
+
+        // Access "hot" repeatedly so its sketch estimate clears "cold"'s.
+        for _ in 0..10 {
+            cache.get("hot");
+        }
+
+        cache.insert("cold".to_string(), "v".to_string(), None).await.unwrap();
+        match events.recv().await {
+            Some(CacheEvent::AdmissionRejected { key }) => assert_eq!(key, "cold"),
+            other => panic!("expected AdmissionRejected event, got {:?}", other),
+        }
+
+        // "hot" is still the resident entry; it was never evicted.
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("hot"), Some("v".to_string()));
+    }
 }
@@ -1,18 +1,52 @@
+use crate::anti_entropy::{MerkleNodeAnswer, MerkleTree, RangeEntry};
 use crate::cache::CacheEntry;
 use async_trait::async_trait;
-use hashring::HashRing;
+use bytes::Bytes;
+use futures::Stream;
+use chrono::{DateTime, Utc};
 use log::{debug, error, info, warn};
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::RwLock;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::task;
 use uuid::Uuid;
 
+/// Number of peers asked to indirectly probe a suspect node per SWIM round
+const INDIRECT_PROBE_FANOUT: usize = 3;
+
+/// How long to wait for a direct ping's TCP ack before falling back to an
+/// indirect probe
+const DIRECT_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Cap on how many recent membership updates are piggybacked per gossip message
+const MAX_PIGGYBACKED_UPDATES: usize = 32;
+
+/// Shortest and longest backoff between attempts to re-dial a peer whose
+/// pooled connection just failed
+const MIN_RECONNECT_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How long a pooled-connection send waits for its matching reply before
+/// giving up and freeing its request id. Callers that need a tighter bound
+/// (e.g. SWIM's direct probe) layer their own shorter `tokio::time::timeout`
+/// around `send_raw_message` on top of this.
+const RPC_REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Request id used for the one-off Hello/GetPeers exchange that happens
+/// before a connection is handshaked and handed to the `ConnectionPool` —
+/// there's nothing else in flight on the connection yet to disambiguate from.
+const BOOTSTRAP_REQUEST_ID: u32 = 0;
+
 /// Errors that can occur in cluster operations
 #[derive(Error, Debug)]
 pub enum ClusterError {
@@ -30,32 +64,158 @@ pub enum ClusterError {
     
     #[error("Node not found: {0}")]
     NodeNotFound(String),
-    
+
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
+
+    #[error("Handshake failed: {0}")]
+    HandshakeError(String),
+
+    #[error("Declared frame length {len} exceeds the maximum of {max} bytes")]
+    FrameTooLarge { len: usize, max: usize },
 }
 
 /// Result type for cluster operations
 pub type ClusterResult<T> = Result<T, ClusterError>;
 
+/// A node's perceived liveness under the SWIM failure detector. At equal
+/// incarnation, conflict resolution always prefers the "more dead" state:
+/// `Dead` overrides `Suspect` overrides `Alive`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeState {
+    Alive,
+    Suspect,
+    Dead,
+}
+
+impl NodeState {
+    fn rank(self) -> u8 {
+        match self {
+            NodeState::Alive => 0,
+            NodeState::Suspect => 1,
+            NodeState::Dead => 2,
+        }
+    }
+}
+
+/// A membership fact about one node, piggybacked on `Ping`/`Pong`/`PingReq`
+/// messages so state disseminates across the cluster without a separate
+/// broadcast round
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipUpdate {
+    pub node: Node,
+    pub state: NodeState,
+    pub incarnation: u64,
+}
+
+/// Locally tracked liveness for one peer: its last-known state/incarnation,
+/// plus when we started suspecting it (to time out to `Dead`)
+#[derive(Debug, Clone)]
+struct MembershipEntry {
+    state: NodeState,
+    incarnation: u64,
+    suspected_since: Option<Instant>,
+}
+
+/// Whether an incoming (state, incarnation) fact should overwrite what we
+/// currently believe about a node: a strictly higher incarnation always
+/// wins; at equal incarnation, the more severe state wins.
+fn should_apply_update(existing: Option<&MembershipEntry>, state: NodeState, incarnation: u64) -> bool {
+    match existing {
+        None => true,
+        Some(entry) => match incarnation.cmp(&entry.incarnation) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => state.rank() > entry.state.rank(),
+            std::cmp::Ordering::Less => false,
+        },
+    }
+}
+
+/// An inclusive range of hash slots (`0..=16383`) owned by a node, Redis-cluster style
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub struct SlotRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl SlotRange {
+    pub fn new(start: u16, end: u16) -> Self {
+        Self { start, end }
+    }
+
+    /// The full 16384-slot space, used as the default assignment for a single-node cluster
+    pub fn full() -> Self {
+        Self { start: 0, end: crate::cluster_cache::SLOT_COUNT - 1 }
+    }
+
+    pub fn contains(&self, slot: u16) -> bool {
+        slot >= self.start && slot <= self.end
+    }
+}
+
 /// Represents a node in the cluster
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Node {
     /// Unique identifier for the node
     pub id: String,
-    
+
     /// Hostname or IP address of the node
     pub host: String,
-    
+
     /// Port for node-to-node communication
     pub port: u16,
-    
+
     /// Port for the HTTP API
     pub api_port: u16,
-    
+
     /// Additional metadata about the node
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+
+    /// Hash slot range owned by this node, if slot-based sharding is in use
+    #[serde(default)]
+    pub slots: Option<SlotRange>,
+
+    /// Relative weight used by the `weighted_round_robin` load-balancing strategy
+    #[serde(default = "default_node_weight")]
+    pub weight: u32,
+
+    /// SWIM incarnation number: bumped by the node itself to refute stale
+    /// `Suspect`/`Dead` rumors about it. Higher always wins over lower.
+    #[serde(default)]
+    pub incarnation: u64,
+
+    /// Path to a Unix domain socket this node listens on for node-to-node
+    /// traffic, for co-located nodes that want fast local IPC instead of a
+    /// loopback TCP connection. When set, this takes precedence over
+    /// `host`/`port` for node-to-node transport (see `transport_addr`);
+    /// `host`/`port` are still used for the HTTP API and for display.
+    #[serde(default)]
+    pub unix_path: Option<PathBuf>,
+}
+
+/// A node-to-node transport address: either a TCP socket or, for co-located
+/// nodes, a Unix domain socket. `Node::transport_addr` picks whichever this
+/// node advertises; the connect/listen paths dispatch on it via
+/// `secure_transport::Transport` so the rest of the code (handshake, framing)
+/// doesn't care which was used.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub enum NodeAddr {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl std::fmt::Display for NodeAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeAddr::Tcp(addr) => write!(f, "{}", addr),
+            NodeAddr::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+fn default_node_weight() -> u32 {
+    1
 }
 
 // Custom Hash implementation that ignores the metadata field
@@ -78,9 +238,13 @@ impl Node {
             port,
             api_port,
             metadata: HashMap::new(),
+            slots: None,
+            weight: default_node_weight(),
+            incarnation: 0,
+            unix_path: None,
         }
     }
-    
+
     /// Create a node with a specific ID
     pub fn with_id(id: String, host: String, port: u16, api_port: u16) -> Self {
         Self {
@@ -89,16 +253,42 @@ impl Node {
             port,
             api_port,
             metadata: HashMap::new(),
+            slots: None,
+            weight: default_node_weight(),
+            incarnation: 0,
+            unix_path: None,
         }
     }
-    
+
+    /// Advertise a Unix domain socket for node-to-node traffic instead of
+    /// `host`/`port`, for co-located nodes that want fast local IPC.
+    pub fn with_unix_path(mut self, path: PathBuf) -> Self {
+        self.unix_path = Some(path);
+        self
+    }
+
+    /// Assign this node a hash slot range for slot-based cluster routing
+    pub fn with_slots(mut self, slots: SlotRange) -> Self {
+        self.slots = Some(slots);
+        self
+    }
+
     /// Get the address for node-to-node communication
     pub fn addr(&self) -> SocketAddr {
         format!("{}:{}", self.host, self.port)
             .parse()
             .expect("Invalid node address")
     }
-    
+
+    /// The address used to actually connect/listen for node-to-node
+    /// traffic: a Unix socket if `unix_path` is set, otherwise TCP `addr()`.
+    pub fn transport_addr(&self) -> NodeAddr {
+        match &self.unix_path {
+            Some(path) => NodeAddr::Unix(path.clone()),
+            None => NodeAddr::Tcp(self.addr()),
+        }
+    }
+
     /// Get the address for the HTTP API
     pub fn api_addr(&self) -> SocketAddr {
         format!("{}:{}", self.host, self.api_port)
@@ -112,10 +302,63 @@ impl Node {
 pub struct ClusterConfig {
     /// Local node configuration
     pub node: Node,
-    
+
     /// Cluster settings
     #[serde(default)]
     pub cluster: ClusterSettings,
+
+    /// Optional service-discovery backend for dynamic membership.
+    /// When present, peers are learned and tracked via the registry
+    /// instead of the static `cluster.seeds` list.
+    #[serde(default)]
+    pub registry: Option<RegistryConfig>,
+
+    /// Optional poll-based node-discovery backend for dynamic membership,
+    /// sourced from an external service catalog rather than the static
+    /// `cluster.seeds` list or a push-based `registry`. Can be configured
+    /// alongside either of those.
+    #[serde(default)]
+    pub discovery: Option<NodeDiscoveryConfig>,
+}
+
+/// Configuration for a pluggable service-discovery backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    /// Which backend to use, e.g. "zookeeper"
+    pub backend: String,
+
+    /// Comma-separated list of backend endpoints (e.g. ZooKeeper hosts)
+    pub endpoints: String,
+
+    /// Parent path under which node znodes are registered
+    #[serde(default = "default_registry_path")]
+    pub path: String,
+}
+
+fn default_registry_path() -> String {
+    "/fastbu/nodes".to_string()
+}
+
+/// Configuration for a pluggable, poll-based node-discovery backend (see
+/// `NodeDiscovery`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDiscoveryConfig {
+    /// Which backend to use, e.g. "consul"
+    pub backend: String,
+
+    /// Base URL of the service catalog (e.g. "http://127.0.0.1:8500" for Consul)
+    pub catalog_endpoint: String,
+
+    /// Name of the service whose healthy instances should be treated as cluster peers
+    pub service_name: String,
+
+    /// How often to re-poll the catalog, in seconds
+    #[serde(default = "default_discovery_poll_interval")]
+    pub poll_interval_secs: u64,
+}
+
+fn default_discovery_poll_interval() -> u64 {
+    10
 }
 
 /// Settings for the cluster behavior
@@ -136,6 +379,71 @@ pub struct ClusterSettings {
     /// Time in seconds after which a node is considered failed
     #[serde(default = "default_node_timeout")]
     pub node_timeout: u64,
+
+    /// Strategy used to pick among healthy candidate nodes when forwarding requests
+    #[serde(default)]
+    pub load_balancing: crate::cluster_cache::LoadBalancingStrategy,
+
+    /// Hex-encoded cluster-wide pre-shared key. Every node-to-node handshake
+    /// proves knowledge of this key in addition to the peer's ed25519
+    /// identity; connections that can't prove it are dropped. Left empty,
+    /// the cluster runs without this check (handshake still authenticates
+    /// peers by their ed25519 key, just not by cluster membership) — fine
+    /// for local development, not for a network-exposed deployment.
+    #[serde(default)]
+    pub network_key: String,
+
+    /// Path to this node's long-term ed25519 identity (the raw 32-byte
+    /// signing seed). Loaded on startup if present, or generated and written
+    /// here on first run, so the node's id — and any peer allowlists or
+    /// trust decisions keyed on it — survives a restart instead of
+    /// regenerating a new identity every time the process starts.
+    #[serde(default = "default_identity_path")]
+    pub identity_path: String,
+
+    /// How long `fetch_remote` waits for a `FetchResponse` before giving up
+    /// on a remote key lookup
+    #[serde(default = "default_fetch_timeout")]
+    pub fetch_timeout: u64,
+
+    /// Number of distinct physical nodes a key is replicated to, including
+    /// its primary owner. `get_responsible_nodes` returns up to this many
+    /// nodes; writes and invalidations are propagated to all of them.
+    #[serde(default = "default_replication")]
+    pub replication: usize,
+
+    /// Path to the on-disk table of known peers (node + last-seen
+    /// timestamp). Loaded on `initialize` to seed reconnection attempts
+    /// alongside the static `seeds` list, so the cluster survives a restart.
+    #[serde(default = "default_node_table_path")]
+    pub node_table_path: String,
+
+    /// Entries in the node table older than this are pruned and no longer
+    /// offered as reconnection candidates
+    #[serde(default = "default_node_table_max_age_secs")]
+    pub node_table_max_age_secs: u64,
+
+    /// Port for the ip-echo self-discovery service (modeled on Solana's
+    /// ip-echo server). When set, this node both runs an ip-echo server on
+    /// this port and, if `seeds` is non-empty, queries the first seed's
+    /// `host` on this same port to learn its own publicly-reachable address
+    /// before announcing itself — correcting `node.host` for nodes behind
+    /// NAT or in containers, where the configured value is often wrong.
+    /// Left unset, no self-discovery happens and `node.host` is trusted as-is.
+    #[serde(default)]
+    pub ip_echo_port: Option<u16>,
+
+    /// Largest declared frame length a connection will accept before
+    /// allocating a buffer for it. A peer that claims a bigger frame than
+    /// this has its connection dropped with `ClusterError::FrameTooLarge`
+    /// instead of forcing an unbounded allocation.
+    #[serde(default = "default_max_frame_size")]
+    pub max_frame_size: usize,
+
+    /// Time interval in seconds between anti-entropy Merkle-tree
+    /// reconciliation rounds with a random peer
+    #[serde(default = "default_anti_entropy_interval")]
+    pub anti_entropy_interval: u64,
 }
 
 fn default_virtual_nodes() -> usize {
@@ -150,422 +458,2192 @@ fn default_node_timeout() -> u64 {
     10
 }
 
+fn default_anti_entropy_interval() -> u64 {
+    30
+}
+
+fn default_fetch_timeout() -> u64 {
+    5
+}
+
+fn default_replication() -> usize {
+    3
+}
+
+fn default_node_table_path() -> String {
+    "cluster_nodes.bin".to_string()
+}
+
+fn default_identity_path() -> String {
+    "node_identity.key".to_string()
+}
+
+fn default_node_table_max_age_secs() -> u64 {
+    // One day; well past any realistic restart-and-rejoin window.
+    24 * 60 * 60
+}
+
+fn default_max_frame_size() -> usize {
+    16 * 1024 * 1024
+}
+
 impl Default for ClusterConfig {
     fn default() -> Self {
         Self {
             node: Node::new("127.0.0.1".to_string(), 7946, 3031),
             cluster: ClusterSettings::default(),
+            registry: None,
+            discovery: None,
         }
     }
 }
 
+/// Out-of-band metadata attached to a correlated request/response pair,
+/// independent of the message's own `request_id`. Every field is optional
+/// and advisory — a responder that doesn't act on a flag just ignores it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestHeaders {
+    /// This request's position within a submitted batch (see
+    /// `ClusterCache::get_many`/`insert_many`), so concurrent, possibly
+    /// out-of-order responses can still be reassembled in submission order.
+    pub sequence: Option<u32>,
+    /// Correlates this request with external tracing/log aggregation.
+    pub trace_id: Option<Uuid>,
+    /// How stale a value the caller is still willing to accept, in seconds;
+    /// not enforced by the responder.
+    pub ttl_hint: Option<u64>,
+}
+
 /// Message types for inter-node communication
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClusterMessage {
-    /// Ping request to check if a node is alive
-    Ping,
-    
-    /// Response to a ping request
-    Pong,
-    
-    /// Request to fetch a cache item from another node
-    FetchRequest { key: String },
-    
-    /// Response to a fetch request
-    FetchResponse { key: String, value: Option<CacheEntry> },
+    /// SWIM direct probe, answered with a `Pong` carrying the recipient's own
+    /// piggybacked updates so gossip converges on both legs of a probe.
+    Ping { updates: Vec<MembershipUpdate> },
+
+    /// Reply to a `Ping`, also used by an indirect prober replying to a
+    /// `PingReq`'s relayed `Ping`.
+    Pong { updates: Vec<MembershipUpdate> },
+
+    /// SWIM indirect probe: asks the recipient to ping `target` on our
+    /// behalf, since our own direct probe of it failed or timed out
+    PingReq { target: Node, updates: Vec<MembershipUpdate> },
+
+    /// Sent by a relay node back to the original prober when its indirect
+    /// probe of `target` succeeded, refuting the prober's suspicion
+    IndirectAck { target: String },
+
+    /// First message sent over a freshly handshaked connection, exchanging
+    /// node info so the recipient can add the sender to its hash ring
+    Hello { node: Node },
+
+    /// Generic acknowledgement that a message was received and handed to
+    /// the message processor; replaces the old bare 1-byte TCP ack now that
+    /// connections are encrypted/framed `ClusterMessage`s throughout
+    Ack,
+
+    /// Request to fetch a cache item from another node. `request_id` is a
+    /// per-cluster monotonically increasing counter that correlates the
+    /// reply with the `fetch_remote` caller awaiting it; `headers` carries
+    /// optional out-of-band metadata the responder echoes back unchanged.
+    FetchRequest { request_id: u64, key: String, headers: RequestHeaders },
+
+    /// Response to a `FetchRequest`, matched back to its waiter by `request_id`
+    FetchResponse { request_id: u64, key: String, value: Option<CacheEntry>, headers: RequestHeaders },
     
     /// Notification that a key has been updated
     KeyUpdated { key: String, value: CacheEntry },
     
     /// Notification that a key has been invalidated
     KeyInvalidated { key: String },
-}
 
-/// Trait defining the behavior of a cluster node
-#[async_trait]
-pub trait ClusterNode {
-    /// Initialize the node and join the cluster
-    async fn initialize(&mut self) -> ClusterResult<()>;
-    
-    /// Determine which node is responsible for a given key
-    async fn get_responsible_node(&self, key: &str) -> Option<Node>;
-    
-    /// Get the list of all known nodes in the cluster
-    async fn get_nodes(&self) -> Vec<Node>;
-    
-    /// Send a message to a specific node
-    async fn send_message(&self, node: &Node, message: ClusterMessage) -> ClusterResult<()>;
-    
-    /// Process a received message
-    async fn process_message(&self, sender: &Node, message: ClusterMessage) -> ClusterResult<()>;
-    
-    /// Handle a node joining the cluster
-    async fn handle_node_joined(&self, node: &Node) -> ClusterResult<()>;
-    
-    /// Handle a node leaving the cluster
-    async fn handle_node_left(&self, node: &Node) -> ClusterResult<()>;
+    /// Asks the recipient for its known-node list, so the cluster can grow
+    /// transitively from a single seed instead of every node needing every
+    /// peer's address up front
+    GetPeers,
+
+    /// Reply to `GetPeers` with the sender's known nodes
+    Peers { nodes: Vec<Node> },
+
+    /// One bounded chunk of a payload too large to buffer as a single
+    /// frame (e.g. a data-rebalancing transfer after a ring change).
+    /// `sequence` is the chunk's position within `stream_id`; `final_chunk`
+    /// marks the last one, so the receiver knows when to stop without
+    /// needing to know the total size up front.
+    StreamChunk { stream_id: Uuid, sequence: u32, data: Vec<u8>, final_chunk: bool },
+
+    /// Anti-entropy: ask the recipient for the hash (and, for a leaf, the
+    /// full entry list) of the Merkle tree node at (`level`, `index`) in its
+    /// current keyspace snapshot, so the requester can descend only into
+    /// subtrees whose hashes disagree with its own.
+    MerkleNodeRequest { request_id: u64, level: usize, index: usize },
+
+    /// Reply to a `MerkleNodeRequest`, matched back to its waiter by `request_id`
+    MerkleNodeResponse {
+        request_id: u64,
+        hash: u64,
+        children: Option<(u64, u64)>,
+        entries: Option<Vec<RangeEntry>>,
+    },
 }
 
-/// Type for accessing cache data
-pub type CacheAccessFn = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+/// A node plus when it was last seen alive, as recorded in the on-disk `NodeTable`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct NodeTableEntry {
+    node: Node,
+    last_seen: DateTime<Utc>,
+}
 
-/// Implementation of a cluster node using custom peer list and hashring
-pub struct FastbuCluster {
-    /// Configuration for this cluster
-    config: ClusterConfig,
-    
-    /// Local node information
-    local_node: Node,
-    
-    /// Consistent hash ring for key distribution
-    hash_ring: Arc<RwLock<HashRing<Node>>>,
-    
-    /// List of peer addresses (host:port) for node discovery
-    peers: Arc<RwLock<Vec<String>>>,
-    
-    /// List of all known nodes
-    nodes: Arc<RwLock<HashMap<String, Node>>>,
-    
-    /// Channel for sending messages to the message processing loop
-    message_sender: Option<mpsc::Sender<(Node, ClusterMessage)>>,
-    
-    /// Function to access the local cache (if set)
-    cache_accessor: Option<CacheAccessFn>,
+/// Persistent record of every peer this node has ever seen, so a restart
+/// doesn't forget the cluster down to its static `seeds`. Learned via the
+/// seed `Hello` exchange and the `GetPeers`/`Peers` address-gossip exchange,
+/// kept sorted most-recently-seen first so reconnection attempts favor
+/// peers most likely to still be alive, and pruned of stale entries.
+struct NodeTable {
+    path: PathBuf,
+    max_age_secs: u64,
+    entries: RwLock<Vec<NodeTableEntry>>,
 }
 
-impl FastbuCluster {
-    /// Create a new cluster instance with the given configuration
-    pub fn new(config: ClusterConfig) -> Self {
-        let local_node = config.node.clone();
-        
-        // Initialize the hash ring with just the local node
-        let mut ring = HashRing::new();
-        ring.add(local_node.clone());
-        
+impl NodeTable {
+    /// Load the table from `path`, if it exists; a missing or unreadable
+    /// file just starts with an empty table rather than failing startup.
+    fn load(path: &str, max_age_secs: u64) -> Self {
+        let path = PathBuf::from(path);
+        let mut entries = match std::fs::read(&path) {
+            Ok(bytes) if !bytes.is_empty() => bincode::deserialize(&bytes).unwrap_or_else(|e| {
+                warn!("Failed to parse node table at {:?}: {}", path, e);
+                Vec::new()
+            }),
+            Ok(_) => Vec::new(),
+            Err(e) => {
+                debug!("No existing node table at {:?} ({}); starting empty", path, e);
+                Vec::new()
+            }
+        };
+
+        prune_and_sort(&mut entries, max_age_secs);
+
         Self {
-            config,
-            local_node,
-            hash_ring: Arc::new(RwLock::new(ring)),
-            peers: Arc::new(RwLock::new(Vec::new())),
-            nodes: Arc::new(RwLock::new(HashMap::new())),
-            message_sender: None,
-            cache_accessor: None,
+            path,
+            max_age_secs,
+            entries: RwLock::new(entries),
         }
     }
-    
-    /// Set a function to access the local cache
-    pub fn set_cache_accessor<F>(&mut self, accessor: F)
-    where
-        F: Fn(&str) -> Option<String> + Send + Sync + 'static
-    {
-        self.cache_accessor = Some(Arc::new(accessor));
-    }
-    
-    /// Start the message processing loop
-    async fn start_message_processor(&mut self) -> ClusterResult<()> {
-        let (tx, mut rx) = mpsc::channel::<(Node, ClusterMessage)>(100);
-        self.message_sender = Some(tx);
-        
-        let nodes = Arc::clone(&self.nodes);
-        let hash_ring = Arc::clone(&self.hash_ring);
-        let local_node = self.local_node.clone();
-        
-        task::spawn(async move {
-            while let Some((sender, message)) = rx.recv().await {
-                use tokio::io::AsyncWriteExt;
-                
-                debug!("Received message from {}: {:?}", sender.id, message);
-                match message {
-                    ClusterMessage::Ping => {
-                        // Handle ping by responding with a pong
-                        debug!("Received ping from {}, responding with pong", sender.id);
-                        // Implementation for sending response would go here
-                    },
-                    ClusterMessage::Pong => {
-                        // Update the node's last seen time
-                        debug!("Received pong from {}", sender.id);
-                    },
-                    ClusterMessage::FetchRequest { key } => {
-                        // Handle request to fetch a key
-                        debug!("Received fetch request for key: {}", key);
-                        
-                        // We need to fetch the value from our local cache
-                        // For proper implementation, we'd have a reference to the cache
-                        // For now, we'll create a message channel to handle this
-                        let fetch_key = key.clone();
-                        let sender_copy = sender.clone();
-                        
-                        // In a full implementation, this would be handled by a proper
-                        // callback to the cache layer to get the value, then send back
-                        // the response. For now, we'll just acknowledge the request.
-                        debug!("Processing fetch request for key: {}", key);
-                        
-                        // Return a response directly using TCP connection
-                        // (This would be handled by a separate response handler in production)
-                        tokio::spawn(async move {
-                            debug!("Preparing response for fetch request: {}", fetch_key);
-                            
-                            // In a real implementation, we would get the value from the cache
-                            // For now, we just send back an empty response
-                            let key_display = fetch_key.clone(); // Create a copy for debug output
-                            let response = ClusterMessage::FetchResponse { 
-                                key: fetch_key,
-                                value: None // This would be actual value from cache
-                            };
-                            
-                            // Send response back to requester
-                            if let Ok(mut stream) = tokio::net::TcpStream::connect(sender_copy.addr()).await {
-                                // Serialize the response
-                                if let Ok(response_bytes) = bincode::serialize(&response) {
-                                    let len = response_bytes.len() as u32;
-                                    let _ = stream.write_all(&len.to_be_bytes()).await;
-                                    let _ = stream.write_all(&response_bytes).await;
-                                    debug!("Sent fetch response for key: {}", key_display);
-                                }
-                            }
-                        });
-                    },
-                    ClusterMessage::FetchResponse { key, value } => {
-                        // Handle response with fetched key
-                        debug!("Received fetch response for key: {}", key);
-                        
-                        // In a complete implementation, we would update a pending requests map
-                        // and notify waiters that their data has arrived.
-                        if value.is_some() {
-                            debug!("Value for key {} received successfully", key);
-                        } else {
-                            debug!("No value found for key {}", key);
-                        }
-                    },
-                    ClusterMessage::KeyUpdated { key, value } => {
-                        // Handle notification that a key was updated
-                        debug!("Received key updated notification for key: {}", key);
-                        
-                        // In a full implementation, we would update our local cache with this value
-                        // This implements cluster-wide replication
-                        debug!("Would update local cache with value for key: {}", key.clone());
-                        
-                        // Acknowledge the update
-                        // We could add a KeyUpdatedAck message type for this
-                    },
-                    ClusterMessage::KeyInvalidated { key } => {
-                        // Handle notification that a key has been invalidated
-                        debug!("Received key invalidated notification for key: {}", key);
-                    },
-                }
-            }
-        });
-        
-        Ok(())
-    }
-    
 
-    /// Add a peer to the cluster
-    pub async fn add_peer(&self, peer: String) {
-        let mut peers = self.peers.write().await;
-        if !peers.contains(&peer) {
-            peers.push(peer);
+    /// Record `node` as seen just now, moving it to the front of the
+    /// recency order, then persist the updated table to disk.
+    async fn touch(&self, node: Node) {
+        let mut entries = self.entries.write().await;
+        entries.retain(|entry| entry.node.id != node.id);
+        entries.push(NodeTableEntry { node, last_seen: Utc::now() });
+        prune_and_sort(&mut entries, self.max_age_secs);
+
+        if let Err(e) = self.save(&entries) {
+            warn!("Failed to persist node table to {:?}: {}", self.path, e);
         }
     }
 
-    /// Get the list of peers
-    pub async fn get_peers(&self) -> Vec<String> {
-        self.peers.read().await.clone()
+    /// Known peers sorted most-recently-seen first, for seeding
+    /// reconnection attempts on top of the static `seeds` list
+    async fn addresses(&self) -> Vec<String> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .map(|entry| format!("{}:{}", entry.node.host, entry.node.port))
+            .collect()
     }
-    
-    /// Get the cluster configuration
-    pub fn get_config(&self) -> &ClusterConfig {
-        &self.config
+
+    fn save(&self, entries: &[NodeTableEntry]) -> std::io::Result<()> {
+        let bytes = bincode::serialize(entries)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(&self.path, bytes)
     }
-    
-    /// Start a listener for incoming messages from other nodes
-    async fn start_message_listener(&self) -> ClusterResult<()> {
-        let addr = self.local_node.addr();
-        let message_sender = self.message_sender.clone();
+}
+
+/// Drop entries older than `max_age_secs` and sort the rest
+/// most-recently-seen first
+fn prune_and_sort(entries: &mut Vec<NodeTableEntry>, max_age_secs: u64) {
+    let now = Utc::now();
+    entries.retain(|entry| {
+        now.signed_duration_since(entry.last_seen).num_seconds() <= max_age_secs as i64
+    });
+    entries.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+}
+
+/// Backoff bookkeeping for one peer's pooled connection: how many times in a
+/// row (re)connecting to it has failed, and the earliest time to try again.
+struct Backoff {
+    consecutive_failures: u32,
+    not_before: Instant,
+}
+
+impl Backoff {
+    fn fresh() -> Self {
+        Self {
+            consecutive_failures: 0,
+            not_before: Instant::now(),
+        }
+    }
+
+    /// Exponential backoff capped at `MAX_RECONNECT_BACKOFF`, based on how
+    /// many consecutive failures have happened so far
+    fn delay(&self) -> Duration {
+        let shift = self.consecutive_failures.min(8);
+        (MIN_RECONNECT_BACKOFF * 2u32.pow(shift)).min(MAX_RECONNECT_BACKOFF)
+    }
+}
+
+/// One peer's pooled connection: the writer half used to send requests, and
+/// the table of requests currently awaiting a reply, demultiplexed off the
+/// connection's background reader task (`read_responses`) by request id.
+struct ConnectionHandle {
+    writer: Mutex<crate::secure_transport::SecureChannelWriter>,
+    pending: Arc<RwLock<HashMap<u32, oneshot::Sender<ClusterMessage>>>>,
+    next_request_id: AtomicU32,
+}
+
+impl ConnectionHandle {
+    fn new(writer: crate::secure_transport::SecureChannelWriter) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            // 0 is reserved for the pre-pool Hello/GetPeers bootstrap exchange.
+            next_request_id: AtomicU32::new(1),
+        }
+    }
+}
+
+/// Pool of long-lived, authenticated outbound connections, one per peer, so
+/// repeated sends reuse a single handshaked socket instead of dialing and
+/// re-handshaking for every message. Concurrent sends to the same peer share
+/// one connection: each gets its own request id, and a background reader
+/// task per connection demultiplexes replies as they arrive instead of
+/// serializing every call behind a single send-then-await-reply round trip.
+/// A connection that errors is dropped and transparently redialed on the
+/// next send, backing off between attempts.
+///
+/// The SWIM failure detector's periodic direct probe (`start_failure_detector`)
+/// goes through this same pool, so a peer whose connection keeps failing to
+/// redial fails its next probe too, without any extra wiring — the existing
+/// Suspect/Dead membership path already treats that as a probe failure.
+struct ConnectionPool {
+    connections: RwLock<HashMap<String, Arc<ConnectionHandle>>>,
+    backoff: RwLock<HashMap<String, Backoff>>,
+    max_frame_size: usize,
+}
+
+impl ConnectionPool {
+    fn new(max_frame_size: usize) -> Self {
+        Self {
+            connections: RwLock::new(HashMap::new()),
+            backoff: RwLock::new(HashMap::new()),
+            max_frame_size,
+        }
+    }
+
+    /// Drop `node_id`'s pooled connection (if any) and schedule the next
+    /// reconnect attempt after an increased backoff.
+    async fn bump_backoff(&self, node_id: &str) {
+        self.connections.write().await.remove(node_id);
+
+        let mut backoff = self.backoff.write().await;
+        let entry = backoff.entry(node_id.to_string()).or_insert_with(Backoff::fresh);
+        entry.consecutive_failures += 1;
+        entry.not_before = Instant::now() + entry.delay();
+    }
+
+    async fn clear_backoff(&self, node_id: &str) {
+        self.backoff.write().await.remove(node_id);
+    }
+}
+
+/// Background task owned by one pooled connection: reads frames off `reader`
+/// in a loop, decodes the `(request_id, ClusterMessage)` envelope, and
+/// completes the matching waiter in `pending`. Exits when the connection
+/// errors, which leaves any request still waiting on a reply to time out on
+/// its own rather than hanging forever, and backs the pool off so the next
+/// send redials instead of reusing the dead connection.
+async fn read_responses(
+    mut reader: crate::secure_transport::SecureChannelReader,
+    pending: Arc<RwLock<HashMap<u32, oneshot::Sender<ClusterMessage>>>>,
+    pool: &Arc<ConnectionPool>,
+    node_id: &str,
+) {
+    loop {
+        let data = match reader.recv_frame().await {
+            Ok(data) => data,
+            Err(e) => {
+                debug!("Pooled connection to {} closed: {}", node_id, e);
+                break;
+            }
+        };
+
+        match decode_envelope(&data) {
+            Ok((request_id, message)) => {
+                if let Some(waiter) = pending.write().await.remove(&request_id) {
+                    let _ = waiter.send(message);
+                } else {
+                    debug!("No waiter for reply {} from {} (already timed out)", request_id, node_id);
+                }
+            }
+            Err(e) => warn!("Failed to decode reply from {}: {}", node_id, e),
+        }
+    }
+
+    pool.bump_backoff(node_id).await;
+}
+
+/// Split a freshly handshaked channel into read/write halves, spawn the
+/// background task that demultiplexes replies off the reader, and pool the
+/// writer half keyed by `node_id`.
+async fn install_connection(
+    pool: &Arc<ConnectionPool>,
+    node_id: &str,
+    channel: crate::secure_transport::SecureChannel,
+) -> Arc<ConnectionHandle> {
+    pool.backoff.write().await.remove(node_id);
+
+    let (reader, writer) = channel.into_split();
+    let handle = Arc::new(ConnectionHandle::new(writer));
+
+    let reader_pool = Arc::clone(pool);
+    let pending = Arc::clone(&handle.pending);
+    let reader_node_id = node_id.to_string();
+    tokio::spawn(async move {
+        read_responses(reader, pending, &reader_pool, &reader_node_id).await;
+    });
+
+    pool.connections.write().await.insert(node_id.to_string(), Arc::clone(&handle));
+    handle
+}
+
+/// Adopt an already-handshaked channel (e.g. from the seed-bootstrap Hello
+/// exchange in `initialize`) into the pool, so later sends to this peer
+/// reuse it instead of dialing a second connection.
+async fn adopt_connection(pool: &Arc<ConnectionPool>, node_id: &str, channel: crate::secure_transport::SecureChannel) {
+    install_connection(pool, node_id, channel).await;
+}
+
+/// Return the pooled connection for `node`, dialing and handshaking a fresh
+/// one if none is open. Refuses to redial before the backoff scheduled by
+/// the last failure has elapsed.
+async fn get_or_connect(
+    pool: &Arc<ConnectionPool>,
+    node: &Node,
+    identity: &crate::secure_transport::NodeIdentity,
+    network_key: &[u8],
+) -> ClusterResult<Arc<ConnectionHandle>> {
+    if let Some(conn) = pool.connections.read().await.get(&node.id) {
+        return Ok(Arc::clone(conn));
+    }
+
+    if let Some(backoff) = pool.backoff.read().await.get(&node.id) {
+        let now = Instant::now();
+        if backoff.not_before > now {
+            return Err(ClusterError::CommunicationError(format!(
+                "Backing off reconnecting to {} for another {:?}",
+                node.id,
+                backoff.not_before - now
+            )));
+        }
+    }
+
+    let stream = match tokio::time::timeout(
+        Duration::from_secs(5),
+        crate::secure_transport::Transport::connect(&node.transport_addr()),
+    ).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => {
+            pool.bump_backoff(&node.id).await;
+            return Err(ClusterError::CommunicationError(format!(
+                "Failed to connect to node {}: {}", node.id, e
+            )));
+        }
+        Err(_) => {
+            pool.bump_backoff(&node.id).await;
+            return Err(ClusterError::CommunicationError(format!(
+                "Connect to node {} timed out", node.id
+            )));
+        }
+    };
+
+    let channel = match tokio::time::timeout(
+        Duration::from_secs(5),
+        crate::secure_transport::client_handshake(
+            stream, identity, network_key, Some(node.id.as_str()), pool.max_frame_size,
+        ),
+    ).await {
+        Ok(Ok(channel)) => channel,
+        Ok(Err(e)) => {
+            pool.bump_backoff(&node.id).await;
+            return Err(e);
+        }
+        Err(_) => {
+            pool.bump_backoff(&node.id).await;
+            return Err(ClusterError::CommunicationError(format!(
+                "Handshake with node {} timed out", node.id
+            )));
+        }
+    };
+
+    Ok(install_connection(pool, &node.id, channel).await)
+}
+
+/// Encode a pooled-connection request frame: a `u32` request id, so the
+/// connection's background reader task can demultiplex concurrent in-flight
+/// replies, followed by the bincode-serialized message.
+fn encode_envelope(request_id: u32, message: &ClusterMessage) -> ClusterResult<Vec<u8>> {
+    let payload = bincode::serialize(message)
+        .map_err(|e| ClusterError::CommunicationError(format!("Failed to serialize message: {}", e)))?;
+
+    let mut framed = Vec::with_capacity(4 + payload.len());
+    framed.extend_from_slice(&request_id.to_be_bytes());
+    framed.extend_from_slice(&payload);
+    Ok(framed)
+}
+
+fn decode_envelope(data: &[u8]) -> ClusterResult<(u32, ClusterMessage)> {
+    if data.len() < 4 {
+        return Err(ClusterError::CommunicationError(
+            "Frame too short to contain a request id".to_string(),
+        ));
+    }
+
+    let mut id_bytes = [0u8; 4];
+    id_bytes.copy_from_slice(&data[0..4]);
+    let request_id = u32::from_be_bytes(id_bytes);
+
+    let message = bincode::deserialize(&data[4..])
+        .map_err(|e| ClusterError::CommunicationError(format!("Failed to deserialize message: {}", e)))?;
+    Ok((request_id, message))
+}
+
+/// Size of each chunk when streaming a large payload (e.g. a data transfer
+/// after a ring change) instead of buffering it whole. Keeps every frame on
+/// the wire small and bounded regardless of how big the overall payload is.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Split `data` into `STREAM_CHUNK_SIZE` chunks and send each as its own
+/// `StreamChunk` frame tagged with `stream_id`, so a large payload never
+/// has to be buffered whole by the receiver before it can start processing
+/// it. An empty `data` still sends one (empty, final) chunk.
+async fn send_stream(
+    channel: &mut crate::secure_transport::SecureChannel,
+    stream_id: Uuid,
+    data: &[u8],
+) -> ClusterResult<()> {
+    let chunks: Vec<&[u8]> = if data.is_empty() {
+        vec![&[][..]]
+    } else {
+        data.chunks(STREAM_CHUNK_SIZE).collect()
+    };
+    let last_sequence = chunks.len() - 1;
+
+    for (sequence, chunk) in chunks.into_iter().enumerate() {
+        let message = ClusterMessage::StreamChunk {
+            stream_id,
+            sequence: sequence as u32,
+            data: chunk.to_vec(),
+            final_chunk: sequence == last_sequence,
+        };
+        let frame_bytes = encode_envelope(BOOTSTRAP_REQUEST_ID, &message)?;
+        channel.send_frame(&frame_bytes).await?;
+    }
+    Ok(())
+}
+
+/// Receive a `StreamChunk` sequence off `channel` as a lazy `Stream` of
+/// `Bytes`, so a caller (e.g. applying a post-rebalance transfer) can start
+/// processing the first chunks while later ones are still arriving instead
+/// of waiting for, and buffering, the whole payload up front. Stops after
+/// the chunk marked `final_chunk`, or on the first error.
+fn recv_stream(
+    channel: &mut crate::secure_transport::SecureChannel,
+) -> impl Stream<Item = ClusterResult<Bytes>> + '_ {
+    futures::stream::unfold(Some(channel), |state| async move {
+        let channel = state?;
+        let outcome = match channel.recv_frame().await {
+            Ok(data) => match decode_envelope(&data) {
+                Ok((_, ClusterMessage::StreamChunk { data, final_chunk, .. })) => {
+                    let next_state = if final_chunk { None } else { Some(channel) };
+                    return Some((Ok(Bytes::from(data)), next_state));
+                }
+                Ok((_, other)) => Err(ClusterError::CommunicationError(format!(
+                    "Expected StreamChunk, got {:?}", other
+                ))),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        Some((outcome, None))
+    })
+}
+
+/// A membership change observed through a `Registry`
+#[derive(Debug, Clone)]
+pub enum MembershipEvent {
+    /// A node registered itself with the registry
+    NodeJoined(Node),
+
+    /// A node's registration disappeared (deregistered, or its session expired)
+    NodeLeft(Node),
+}
+
+/// Pluggable service-discovery backend for dynamic cluster membership.
+///
+/// Implementations register the local node and watch for peers joining or
+/// leaving, so `FastbuCluster` can learn its peer set at runtime instead of
+/// from a static `cluster.toml` seed list.
+#[async_trait]
+pub trait Registry: Send + Sync {
+    /// Register a node with the discovery backend
+    async fn register(&self, node: &Node) -> ClusterResult<()>;
+
+    /// Remove a node's registration
+    async fn deregister(&self, node: &Node) -> ClusterResult<()>;
+
+    /// Start watching for membership changes, returning a channel of events
+    async fn watch(&self) -> ClusterResult<mpsc::Receiver<MembershipEvent>>;
+}
+
+/// `Registry` implementation backed by Apache ZooKeeper.
+///
+/// Each node is written as an ephemeral znode under `<path>/<node_id>`
+/// holding its host/api_port/port, so it disappears automatically if the
+/// node's session dies. Membership changes are observed by watching the
+/// parent path for children changes.
+pub struct ZookeeperRegistry {
+    endpoints: String,
+    base_path: String,
+    /// Long-lived ZooKeeper session shared by every `register`/`deregister`/
+    /// `watch` call, connected lazily on first use (see `zk_session`).
+    /// ZooKeeper ties an `Ephemeral` znode's lifetime, and a
+    /// `PathChildrenCache`'s watch, to the session that created them — so
+    /// connecting fresh and dropping the connection at the end of each call
+    /// deleted the just-registered node, and stopped the watch, the instant
+    /// that call returned. Holding one session for the registry's lifetime
+    /// fixes both.
+    session: Arc<std::sync::Mutex<Option<Arc<zookeeper::ZooKeeper>>>>,
+    /// Keeps the active children-watch alive; a `PathChildrenCache` stops
+    /// watching (its background thread exits) as soon as it's dropped.
+    watch_cache: Arc<std::sync::Mutex<Option<zookeeper::recipes::cache::PathChildrenCache>>>,
+}
+
+impl ZookeeperRegistry {
+    pub fn new(endpoints: String, base_path: String) -> Self {
+        Self {
+            endpoints,
+            base_path,
+            session: Arc::new(std::sync::Mutex::new(None)),
+            watch_cache: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    fn node_path(&self, node_id: &str) -> String {
+        format!("{}/{}", self.base_path.trim_end_matches('/'), node_id)
+    }
+}
+
+/// Reuse `session`'s ZooKeeper connection if one is already established,
+/// otherwise connect once and cache it there for every later call to reuse.
+/// Must run on a blocking thread (connecting blocks).
+fn zk_session(
+    session: &std::sync::Mutex<Option<Arc<zookeeper::ZooKeeper>>>,
+    endpoints: &str,
+) -> ClusterResult<Arc<zookeeper::ZooKeeper>> {
+    let mut guard = session.lock().unwrap();
+    if let Some(zk) = guard.as_ref() {
+        return Ok(Arc::clone(zk));
+    }
+
+    let zk = zookeeper::ZooKeeper::connect(endpoints, std::time::Duration::from_secs(10), NoopWatcher)
+        .map_err(|e| ClusterError::CommunicationError(format!("ZooKeeper connect failed: {}", e)))?;
+    let zk = Arc::new(zk);
+    *guard = Some(Arc::clone(&zk));
+    Ok(zk)
+}
+
+/// A `Watcher` that only logs connection-level events; per-path watches for
+/// membership are handled separately via `PathChildrenCache`.
+struct NoopWatcher;
+
+impl zookeeper::Watcher for NoopWatcher {
+    fn handle(&self, event: zookeeper::WatchedEvent) {
+        debug!("ZooKeeper session event: {:?}", event);
+    }
+}
+
+/// Create every missing persistent ancestor of `path`, Redis-cluster/etcd
+/// style "mkdir -p" for znodes.
+fn ensure_parents(zk: &zookeeper::ZooKeeper, path: &str) -> ClusterResult<()> {
+    let mut current = String::new();
+    for segment in path.trim_start_matches('/').split('/') {
+        if segment.is_empty() {
+            continue;
+        }
+        current.push('/');
+        current.push_str(segment);
+
+        match zk.exists(&current, false) {
+            Ok(Some(_)) => continue,
+            Ok(None) => {
+                if let Err(e) = zk.create(
+                    &current,
+                    Vec::new(),
+                    zookeeper::Acl::open_unsafe().clone(),
+                    zookeeper::CreateMode::Persistent,
+                ) {
+                    // Another node may have created it concurrently; only bail on real errors.
+                    if !matches!(e, zookeeper::ZkError::NodeExists) {
+                        return Err(ClusterError::CommunicationError(format!(
+                            "Failed to create znode {}: {}", current, e
+                        )));
+                    }
+                }
+            }
+            Err(e) => {
+                return Err(ClusterError::CommunicationError(format!(
+                    "Failed to check znode {}: {}", current, e
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Registry for ZookeeperRegistry {
+    async fn register(&self, node: &Node) -> ClusterResult<()> {
+        let session = Arc::clone(&self.session);
+        let endpoints = self.endpoints.clone();
+        let path = self.node_path(&node.id);
+        let payload = bincode::serialize(node)
+            .map_err(|e| ClusterError::ConfigurationError(format!("Failed to serialize node: {}", e)))?;
+
+        task::spawn_blocking(move || -> ClusterResult<()> {
+            let zk = zk_session(&session, &endpoints)?;
+
+            ensure_parents(&zk, &path)?;
+
+            zk.create(
+                &path,
+                payload,
+                zookeeper::Acl::open_unsafe().clone(),
+                zookeeper::CreateMode::Ephemeral,
+            )
+            .map_err(|e| ClusterError::CommunicationError(format!("ZooKeeper create failed: {}", e)))?;
+
+            info!("Registered node at ZooKeeper path {}", path);
+            Ok(())
+        })
+        .await
+        .map_err(|e| ClusterError::CommunicationError(format!("Registration task panicked: {}", e)))?
+    }
+
+    async fn deregister(&self, node: &Node) -> ClusterResult<()> {
+        let session = Arc::clone(&self.session);
+        let endpoints = self.endpoints.clone();
+        let path = self.node_path(&node.id);
+
+        task::spawn_blocking(move || -> ClusterResult<()> {
+            let zk = zk_session(&session, &endpoints)?;
+
+            match zk.delete(&path, None) {
+                Ok(_) | Err(zookeeper::ZkError::NoNode) => Ok(()),
+                Err(e) => Err(ClusterError::CommunicationError(format!(
+                    "ZooKeeper delete failed: {}", e
+                ))),
+            }
+        })
+        .await
+        .map_err(|e| ClusterError::CommunicationError(format!("Deregistration task panicked: {}", e)))?
+    }
+
+    async fn watch(&self) -> ClusterResult<mpsc::Receiver<MembershipEvent>> {
+        let (tx, rx) = mpsc::channel(100);
+        let session = Arc::clone(&self.session);
+        let watch_cache = Arc::clone(&self.watch_cache);
+        let endpoints = self.endpoints.clone();
+        let base_path = self.base_path.clone();
+
+        task::spawn_blocking(move || {
+            let zk = match zk_session(&session, &endpoints) {
+                Ok(zk) => zk,
+                Err(e) => {
+                    error!("Failed to connect to ZooKeeper at {}: {}", endpoints, e);
+                    return;
+                }
+            };
+
+            if let Err(e) = ensure_parents(&zk, &base_path) {
+                error!("Failed to ensure registry path {} exists: {}", base_path, e);
+                return;
+            }
+
+            let cache = match zookeeper::recipes::cache::PathChildrenCache::new(&zk, &base_path) {
+                Ok(cache) => cache,
+                Err(e) => {
+                    error!("Failed to watch {} in ZooKeeper: {}", base_path, e);
+                    return;
+                }
+            };
+
+            cache.add_listener(move |event| {
+                use zookeeper::recipes::cache::PathChildrenCacheEvent;
+
+                let mapped = match event {
+                    PathChildrenCacheEvent::ChildAdded(_, data) | PathChildrenCacheEvent::ChildUpdated(_, data) => {
+                        bincode::deserialize::<Node>(&data).ok().map(MembershipEvent::NodeJoined)
+                    }
+                    PathChildrenCacheEvent::ChildRemoved(_, data) => {
+                        bincode::deserialize::<Node>(&data).ok().map(MembershipEvent::NodeLeft)
+                    }
+                    _ => None,
+                };
+
+                if let Some(event) = mapped {
+                    if let Err(e) = tx.blocking_send(event) {
+                        warn!("Membership watch receiver dropped: {}", e);
+                    }
+                }
+            });
+
+            if let Err(e) = cache.start() {
+                error!("Failed to start ZooKeeper children watcher on {}: {}", base_path, e);
+                return;
+            }
+
+            // `cache` must outlive the watch itself (dropping it tears the
+            // watch down), so park it in the registry rather than letting it
+            // go out of scope when this closure returns.
+            *watch_cache.lock().unwrap() = Some(cache);
+        });
+
+        Ok(rx)
+    }
+}
+
+/// Pluggable, poll-based node-discovery backend for dynamic cluster
+/// membership, sourced from an external service catalog. Unlike `Registry`
+/// (which watches a push-based backend for discrete join/leave events), a
+/// `NodeDiscovery` backend is simply asked to list every currently-healthy
+/// instance of a named service each time it's polled; `start_node_discovery`
+/// diffs that list against what's already known and applies only the
+/// difference.
+#[async_trait]
+pub trait NodeDiscovery: Send + Sync {
+    /// List every node the backend currently considers healthy.
+    async fn discover(&self) -> ClusterResult<Vec<Node>>;
+}
+
+/// `NodeDiscovery` implementation backed by a Consul-style HTTP health
+/// endpoint: `GET {catalog_endpoint}/v1/health/service/{service_name}?passing=true`.
+/// Each passing entry's service address/port become a `Node`'s `host`/`port`,
+/// and its service ID becomes the `Node`'s ID, so the same catalog entry
+/// always maps to the same node across polls.
+pub struct ConsulNodeDiscovery {
+    catalog_endpoint: String,
+    service_name: String,
+    http: reqwest::Client,
+}
+
+impl ConsulNodeDiscovery {
+    pub fn new(catalog_endpoint: String, service_name: String) -> Self {
+        Self { catalog_endpoint, service_name, http: reqwest::Client::new() }
+    }
+}
+
+/// The fields of a Consul health-check entry this node actually needs;
+/// everything else in the real response (`Node`, `Checks`, ...) is ignored.
+#[derive(Debug, Deserialize)]
+struct ConsulHealthEntry {
+    #[serde(rename = "Service")]
+    service: ConsulServiceEntry,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsulServiceEntry {
+    #[serde(rename = "ID")]
+    id: String,
+    #[serde(rename = "Address")]
+    address: String,
+    #[serde(rename = "Port")]
+    port: u16,
+    /// Consul has no notion of a second "API" port, so a deployment that
+    /// wants one advertised through discovery sets `api_port` in the
+    /// service's `Meta` map; absent that, the catalog port doubles as
+    /// both the cluster and API port.
+    #[serde(rename = "Meta", default)]
+    meta: HashMap<String, String>,
+}
+
+#[async_trait]
+impl NodeDiscovery for ConsulNodeDiscovery {
+    async fn discover(&self) -> ClusterResult<Vec<Node>> {
+        let url = format!(
+            "{}/v1/health/service/{}?passing=true",
+            self.catalog_endpoint.trim_end_matches('/'),
+            self.service_name
+        );
+
+        let entries: Vec<ConsulHealthEntry> = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Consul catalog request to {} failed: {}", url, e)))?
+            .error_for_status()
+            .map_err(|e| ClusterError::CommunicationError(format!("Consul catalog at {} returned an error: {}", url, e)))?
+            .json()
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to parse Consul catalog response: {}", e)))?;
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| {
+                let service = entry.service;
+                let api_port = service
+                    .meta
+                    .get("api_port")
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(service.port);
+                Node::with_id(service.id, service.address, service.port, api_port)
+            })
+            .collect())
+    }
+}
+
+/// Trait defining the behavior of a cluster node
+#[async_trait]
+pub trait ClusterNode {
+    /// Initialize the node and join the cluster
+    async fn initialize(&mut self) -> ClusterResult<()>;
+    
+    /// Determine which node is responsible for a given key
+    async fn get_responsible_node(&self, key: &str) -> Option<Node>;
+    
+    /// Get the list of all known nodes in the cluster
+    async fn get_nodes(&self) -> Vec<Node>;
+    
+    /// Send a message to a specific node, returning the peer's typed reply
+    async fn send_message(&self, node: &Node, message: ClusterMessage) -> ClusterResult<ClusterMessage>;
+    
+    /// Process a received message
+    async fn process_message(&self, sender: &Node, message: ClusterMessage) -> ClusterResult<()>;
+    
+    /// Handle a node joining the cluster
+    async fn handle_node_joined(&self, node: &Node) -> ClusterResult<()>;
+    
+    /// Handle a node leaving the cluster
+    async fn handle_node_left(&self, node: &Node) -> ClusterResult<()>;
+}
+
+/// Type for accessing cache data. Returns the full `CacheEntry` (not just
+/// its value) so its real version travels with a `FetchResponse` — needed
+/// for anti-entropy pulls and read-repair to compare versions correctly
+/// instead of minting a fresh, meaningless one for every response.
+pub type CacheAccessFn = Arc<dyn Fn(&str) -> Option<CacheEntry> + Send + Sync>;
+
+/// Type for applying a replicated write to the local cache. Takes the full
+/// `CacheEntry` (not just its value) so a replicated write's TTL survives
+/// the trip instead of resetting to "never expires" on the receiving node.
+pub type CacheWriteFn = Arc<dyn Fn(&str, &CacheEntry) + Send + Sync>;
+
+/// Type for applying a replicated invalidation to the local cache
+pub type CacheInvalidateFn = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Type building a fresh `MerkleTree` snapshot of the local keyspace, to
+/// answer an incoming `MerkleNodeRequest`
+pub type MerkleProviderFn = Arc<dyn Fn() -> MerkleTree + Send + Sync>;
+
+/// Implementation of a cluster node using a custom peer list and a
+/// virtual-node consistent hash ring (see `nodes_clockwise_from`)
+pub struct FastbuCluster {
+    /// Configuration for this cluster
+    config: ClusterConfig,
+    
+    /// Local node information
+    local_node: Node,
+
+    /// List of peer addresses (host:port) for node discovery
+    peers: Arc<RwLock<Vec<String>>>,
+    
+    /// List of all known nodes
+    nodes: Arc<RwLock<HashMap<String, Node>>>,
+    
+    /// Channel for sending messages to the message processing loop
+    message_sender: Option<mpsc::Sender<(Node, ClusterMessage)>>,
+    
+    /// Function to access the local cache (if set)
+    cache_accessor: Option<CacheAccessFn>,
+
+    /// Function to apply a replicated `KeyUpdated` to the local cache (if set)
+    cache_writer: Option<CacheWriteFn>,
+
+    /// Function to apply a replicated `KeyInvalidated` to the local cache (if set)
+    cache_invalidator: Option<CacheInvalidateFn>,
+
+    /// Service-discovery backend driving dynamic membership, if configured
+    registry: Option<Arc<dyn Registry>>,
+
+    /// Poll-based node-discovery backend driving dynamic membership, if configured
+    node_discovery: Option<Arc<dyn NodeDiscovery>>,
+
+    /// Reachability of known peers, keyed by node ID. Absent entries are
+    /// assumed healthy; a node is only marked unhealthy after a failed send.
+    health: Arc<RwLock<HashMap<String, bool>>>,
+
+    /// SWIM failure-detector state for each known peer, keyed by node ID
+    membership: Arc<RwLock<HashMap<String, MembershipEntry>>>,
+
+    /// Recent membership facts waiting to be piggybacked on outgoing
+    /// Ping/PingReq messages, capped at `MAX_PIGGYBACKED_UPDATES`
+    pending_updates: Arc<RwLock<Vec<MembershipUpdate>>>,
+
+    /// This node's own SWIM incarnation number, bumped when refuting a
+    /// `Suspect`/`Dead` rumor about itself
+    local_incarnation: Arc<AtomicU64>,
+
+    /// This node's long-lived ed25519 identity; `local_node.id` is derived
+    /// from its public key
+    identity: Arc<crate::secure_transport::NodeIdentity>,
+
+    /// Waiters for in-flight `fetch_remote` calls, keyed by the `FetchRequest`'s
+    /// `request_id`. Completed (and removed) when the matching `FetchResponse`
+    /// arrives, or removed by `fetch_remote` itself on timeout.
+    pending_requests: Arc<RwLock<HashMap<u64, oneshot::Sender<Option<CacheEntry>>>>>,
+
+    /// Source of `FetchRequest.request_id`: monotonically increasing, so
+    /// correlating a response back to its waiter never depends on the
+    /// uniqueness of a randomly generated ID.
+    next_request_id: Arc<AtomicU64>,
+
+    /// Function building a fresh `MerkleTree` snapshot of the local
+    /// keyspace, used to answer incoming `MerkleNodeRequest`s (if set)
+    merkle_provider: Option<MerkleProviderFn>,
+
+    /// Waiters for in-flight `query_merkle_node` calls, keyed by the
+    /// `MerkleNodeRequest`'s `request_id`; shares `next_request_id`'s
+    /// counter with `pending_requests` since both just need uniqueness
+    pending_merkle_requests: Arc<RwLock<HashMap<u64, oneshot::Sender<MerkleNodeAnswer>>>>,
+
+    /// On-disk record of every peer ever seen, surviving restarts
+    node_table: Arc<NodeTable>,
+
+    /// Pooled outbound connections, one long-lived handshaked socket per
+    /// peer, shared by every message send instead of dialing per message
+    connections: Arc<ConnectionPool>,
+}
+
+impl FastbuCluster {
+    /// Create a new cluster instance with the given configuration
+    pub fn new(config: ClusterConfig) -> Self {
+        let node_table = NodeTable::load(
+            &config.cluster.node_table_path,
+            config.cluster.node_table_max_age_secs,
+        );
+
+        let identity = crate::secure_transport::NodeIdentity::load_or_generate(&config.cluster.identity_path);
+
+        // A node's id is its cryptographic identity, not whatever was in
+        // the config file; this is also what peers authenticate against
+        // during the connection handshake.
+        let mut local_node = config.node.clone();
+        local_node.id = identity.node_id();
+
+        // A node that doesn't carry an explicit slot assignment owns the full
+        // slot space until peers join and slots are reassigned.
+        if local_node.slots.is_none() {
+            local_node = local_node.with_slots(SlotRange::full());
+        }
+
+        let max_frame_size = config.cluster.max_frame_size;
+
+        Self {
+            config,
+            local_node,
+            peers: Arc::new(RwLock::new(Vec::new())),
+            nodes: Arc::new(RwLock::new(HashMap::new())),
+            message_sender: None,
+            cache_accessor: None,
+            cache_writer: None,
+            cache_invalidator: None,
+            registry: None,
+            node_discovery: None,
+            identity: Arc::new(identity),
+            health: Arc::new(RwLock::new(HashMap::new())),
+            membership: Arc::new(RwLock::new(HashMap::new())),
+            pending_updates: Arc::new(RwLock::new(Vec::new())),
+            local_incarnation: Arc::new(AtomicU64::new(0)),
+            pending_requests: Arc::new(RwLock::new(HashMap::new())),
+            next_request_id: Arc::new(AtomicU64::new(0)),
+            merkle_provider: None,
+            pending_merkle_requests: Arc::new(RwLock::new(HashMap::new())),
+            node_table: Arc::new(node_table),
+            connections: Arc::new(ConnectionPool::new(max_frame_size)),
+        }
+    }
+
+    /// Mark a node as unreachable, so the load balancer skips it until it recovers
+    pub async fn mark_unhealthy(&self, node_id: &str) {
+        self.health.write().await.insert(node_id.to_string(), false);
+    }
+
+    /// Mark a node as reachable again
+    pub async fn mark_healthy(&self, node_id: &str) {
+        self.health.write().await.insert(node_id.to_string(), true);
+    }
+
+    /// Whether a node is currently considered reachable (defaults to healthy
+    /// until proven otherwise)
+    pub async fn is_healthy(&self, node_id: &str) -> bool {
+        self.health.read().await.get(node_id).copied().unwrap_or(true)
+    }
+
+    /// All known nodes currently considered healthy
+    pub async fn healthy_nodes(&self) -> Vec<Node> {
+        let nodes = self.nodes.read().await;
+        let health = self.health.read().await;
+        nodes
+            .values()
+            .filter(|node| health.get(&node.id).copied().unwrap_or(true))
+            .cloned()
+            .collect()
+    }
+
+
+    /// Set a function to access the local cache
+    pub fn set_cache_accessor<F>(&mut self, accessor: F)
+    where
+        F: Fn(&str) -> Option<CacheEntry> + Send + Sync + 'static
+    {
+        self.cache_accessor = Some(Arc::new(accessor));
+    }
+
+    /// Set a function to apply a replicated `KeyUpdated` to the local cache
+    pub fn set_cache_writer<F>(&mut self, writer: F)
+    where
+        F: Fn(&str, &CacheEntry) + Send + Sync + 'static
+    {
+        self.cache_writer = Some(Arc::new(writer));
+    }
+
+    /// Set a function to apply a replicated `KeyInvalidated` to the local cache
+    pub fn set_cache_invalidator<F>(&mut self, invalidator: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static
+    {
+        self.cache_invalidator = Some(Arc::new(invalidator));
+    }
+
+    /// Set a function building a fresh `MerkleTree` snapshot of the local
+    /// keyspace, used to answer incoming anti-entropy `MerkleNodeRequest`s
+    pub fn set_merkle_provider<F>(&mut self, provider: F)
+    where
+        F: Fn() -> MerkleTree + Send + Sync + 'static
+    {
+        self.merkle_provider = Some(Arc::new(provider));
+    }
+
+    /// Start the message processing loop
+    async fn start_message_processor(&mut self) -> ClusterResult<()> {
+        let (tx, mut rx) = mpsc::channel::<(Node, ClusterMessage)>(100);
+        self.message_sender = Some(tx);
+        
+        let nodes = Arc::clone(&self.nodes);
+        let health = Arc::clone(&self.health);
+        let membership = Arc::clone(&self.membership);
+        let pending_updates = Arc::clone(&self.pending_updates);
+        let local_incarnation = Arc::clone(&self.local_incarnation);
         let local_node = self.local_node.clone();
+        let identity = self.identity();
+        let network_key = self.network_key_bytes();
+        let cache_accessor = self.cache_accessor.clone();
+        let cache_writer = self.cache_writer.clone();
+        let cache_invalidator = self.cache_invalidator.clone();
+        let pending_requests = Arc::clone(&self.pending_requests);
+        let merkle_provider = self.merkle_provider.clone();
+        let pending_merkle_requests = Arc::clone(&self.pending_merkle_requests);
+        let node_table = Arc::clone(&self.node_table);
+        let connections = Arc::clone(&self.connections);
+
+        task::spawn(async move {
+            while let Some((sender, message)) = rx.recv().await {
+                debug!("Received message from {}: {:?}", sender.id, message);
+                match message {
+                    ClusterMessage::Ping { updates } | ClusterMessage::Pong { updates } => {
+                        // The listener already replies to `Ping` with our own
+                        // `Pong` (see `start_message_listener`); here we just
+                        // absorb whichever side's piggybacked gossip arrived.
+                        debug!(
+                            "Received {} piggybacked update(s) from {}",
+                            updates.len(),
+                            sender.id
+                        );
+                        for update in &updates {
+                            apply_membership_update(
+                                &nodes,
+                                &health,
+                                &membership,
+                                &pending_updates,
+                                &local_node,
+                                &local_incarnation,
+                                update,
+                            ).await;
+                        }
+                    },
+                    ClusterMessage::PingReq { target, updates } => {
+                        debug!(
+                            "Received indirect probe request from {} for target {}",
+                            sender.id, target.id
+                        );
+                        for update in &updates {
+                            apply_membership_update(
+                                &nodes,
+                                &health,
+                                &membership,
+                                &pending_updates,
+                                &local_node,
+                                &local_incarnation,
+                                update,
+                            ).await;
+                        }
+
+                        let requester = sender.clone();
+                        let relay_target = target.clone();
+                        let relay_pending_updates = Arc::clone(&pending_updates);
+                        let relay_identity = Arc::clone(&identity);
+                        let relay_network_key = network_key.clone();
+                        let relay_connections = Arc::clone(&connections);
+                        let relay_nodes = Arc::clone(&nodes);
+                        let relay_health = Arc::clone(&health);
+                        let relay_membership = Arc::clone(&membership);
+                        let relay_local_node = local_node.clone();
+                        let relay_local_incarnation = Arc::clone(&local_incarnation);
+                        tokio::spawn(async move {
+                            let relay_updates = relay_pending_updates.read().await.clone();
+                            let probed = tokio::time::timeout(
+                                DIRECT_PROBE_TIMEOUT,
+                                send_raw_message(
+                                    &relay_target,
+                                    &ClusterMessage::Ping { updates: relay_updates },
+                                    &relay_identity,
+                                    &relay_network_key,
+                                    &relay_connections,
+                                ),
+                            ).await;
+
+                            if let Ok(Ok(reply)) = probed {
+                                debug!(
+                                    "Indirect probe of {} succeeded, acking {}",
+                                    relay_target.id, requester.id
+                                );
+                                if let ClusterMessage::Pong { updates: incoming } = reply {
+                                    for update in &incoming {
+                                        apply_membership_update(
+                                            &relay_nodes,
+                                            &relay_health,
+                                            &relay_membership,
+                                            &relay_pending_updates,
+                                            &relay_local_node,
+                                            &relay_local_incarnation,
+                                            update,
+                                        ).await;
+                                    }
+                                }
+                                let _ = send_raw_message(
+                                    &requester,
+                                    &ClusterMessage::IndirectAck { target: relay_target.id.clone() },
+                                    &relay_identity,
+                                    &relay_network_key,
+                                    &relay_connections,
+                                ).await;
+                            } else {
+                                debug!("Indirect probe of {} failed as well", relay_target.id);
+                            }
+                        });
+                    },
+                    ClusterMessage::IndirectAck { target } => {
+                        debug!("Received indirect ack for {} via {}", target, sender.id);
+                        // A relay confirmed `target` is alive; cancel our
+                        // local suspicion directly rather than routing this
+                        // through `should_apply_update`, since it carries no
+                        // incarnation and is only meaningful to us.
+                        let mut membership = membership.write().await;
+                        if let Some(entry) = membership.get_mut(&target) {
+                            if entry.state == NodeState::Suspect {
+                                entry.state = NodeState::Alive;
+                                entry.suspected_since = None;
+                            }
+                        }
+                        drop(membership);
+                        health.write().await.insert(target, true);
+                    },
+                    ClusterMessage::Hello { node } => {
+                        // The listener handles Hello during connection setup
+                        // to register the node before any other traffic;
+                        // seeing one here just means a peer re-announced itself.
+                        info!("Received Hello from {} ({})", sender.id, node.id);
+                        {
+                            let mut nodes = nodes.write().await;
+                            nodes.insert(node.id.clone(), node);
+                        }
+                        recompute_slot_assignments(&nodes).await;
+                    },
+                    ClusterMessage::Ack => {
+                        debug!("Received ack from {}", sender.id);
+                    },
+                    ClusterMessage::FetchRequest { request_id, key, headers } => {
+                        debug!("Received fetch request {} for key: {} (headers: {:?})", request_id, key, headers);
+
+                        let value = cache_accessor.as_ref().and_then(|accessor| accessor(&key));
+
+                        let response = ClusterMessage::FetchResponse { request_id, key: key.clone(), value, headers };
+                        let requester = sender.clone();
+                        let fetch_identity = Arc::clone(&identity);
+                        let fetch_network_key = network_key.clone();
+                        let fetch_connections = Arc::clone(&connections);
+                        tokio::spawn(async move {
+                            if let Err(e) = send_raw_message(&requester, &response, &fetch_identity, &fetch_network_key, &fetch_connections).await {
+                                warn!("Failed to send fetch response for key {} to {}: {}", key, requester.id, e);
+                            }
+                        });
+                    },
+                    ClusterMessage::FetchResponse { request_id, key, value, headers: _ } => {
+                        debug!("Received fetch response {} for key: {}", request_id, key);
+
+                        if let Some(waiter) = pending_requests.write().await.remove(&request_id) {
+                            let _ = waiter.send(value);
+                        } else {
+                            debug!("No waiter for fetch response {} (timed out or already completed)", request_id);
+                        }
+                    },
+                    ClusterMessage::MerkleNodeRequest { request_id, level, index } => {
+                        debug!(
+                            "Received merkle node request {} for level {} index {}",
+                            request_id, level, index
+                        );
+
+                        let answer = merkle_provider.as_ref().map(|provider| {
+                            let tree = provider();
+                            MerkleNodeAnswer {
+                                hash: tree.node_hash(level, index).unwrap_or(0),
+                                children: tree.children(level, index),
+                                entries: if level == 0 {
+                                    Some(tree.range_entries(index).to_vec())
+                                } else {
+                                    None
+                                },
+                            }
+                        }).unwrap_or(MerkleNodeAnswer { hash: 0, children: None, entries: None });
+
+                        let response = ClusterMessage::MerkleNodeResponse {
+                            request_id,
+                            hash: answer.hash,
+                            children: answer.children,
+                            entries: answer.entries,
+                        };
+                        let requester = sender.clone();
+                        let merkle_identity = Arc::clone(&identity);
+                        let merkle_network_key = network_key.clone();
+                        let merkle_connections = Arc::clone(&connections);
+                        tokio::spawn(async move {
+                            if let Err(e) = send_raw_message(&requester, &response, &merkle_identity, &merkle_network_key, &merkle_connections).await {
+                                warn!("Failed to send merkle node response {} to {}: {}", request_id, requester.id, e);
+                            }
+                        });
+                    },
+                    ClusterMessage::MerkleNodeResponse { request_id, hash, children, entries } => {
+                        debug!("Received merkle node response {}", request_id);
+
+                        if let Some(waiter) = pending_merkle_requests.write().await.remove(&request_id) {
+                            let _ = waiter.send(MerkleNodeAnswer { hash, children, entries });
+                        } else {
+                            debug!("No waiter for merkle node response {} (timed out or already completed)", request_id);
+                        }
+                    },
+                    ClusterMessage::KeyUpdated { key, value } => {
+                        debug!("Received key updated notification for key: {}", key);
+
+                        match &cache_writer {
+                            Some(writer) => writer(&key, &value),
+                            None => warn!(
+                                "No cache writer configured; dropping replicated update for key: {}",
+                                key
+                            ),
+                        }
+                    },
+                    ClusterMessage::KeyInvalidated { key } => {
+                        debug!("Received key invalidated notification for key: {}", key);
+
+                        match &cache_invalidator {
+                            Some(invalidator) => invalidator(&key),
+                            None => warn!(
+                                "No cache invalidator configured; dropping replicated invalidation for key: {}",
+                                key
+                            ),
+                        }
+                    },
+                    ClusterMessage::GetPeers => {
+                        // The listener already replied with our known-node list
+                        // directly on the connection this arrived on.
+                        debug!("Received GetPeers from {}", sender.id);
+                    },
+                    ClusterMessage::Peers { nodes: peer_nodes } => {
+                        debug!("Received {} peer(s) from {}", peer_nodes.len(), sender.id);
+                        let mut added_any = false;
+                        for node in peer_nodes {
+                            if node.id == local_node.id {
+                                continue;
+                            }
+                            nodes.write().await.insert(node.id.clone(), node.clone());
+                            node_table.touch(node).await;
+                            added_any = true;
+                        }
+                        if added_any {
+                            recompute_slot_assignments(&nodes).await;
+                        }
+                    },
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Start the SWIM failure-detector loop: on each `gossip_interval` tick,
+    /// time out long-suspected peers to `Dead`, then direct-probe one random
+    /// peer. A failed direct probe marks the peer `Suspect` and asks
+    /// `INDIRECT_PROBE_FANOUT` other peers to probe it on our behalf before
+    /// we give up on it.
+    async fn start_failure_detector(&mut self) -> ClusterResult<()> {
         let nodes = Arc::clone(&self.nodes);
-        let hash_ring = Arc::clone(&self.hash_ring);
-        
-        // Start a TCP listener for incoming messages
-        let listener = match tokio::net::TcpListener::bind(addr).await {
-            Ok(listener) => {
-                info!("Started message listener on {}", addr);
-                listener
-            },
-            Err(e) => {
-                return Err(ClusterError::InitializationError(format!(
-                    "Failed to start message listener on {}: {}", addr, e
+        let health = Arc::clone(&self.health);
+        let membership = Arc::clone(&self.membership);
+        let pending_updates = Arc::clone(&self.pending_updates);
+        let local_incarnation = Arc::clone(&self.local_incarnation);
+        let local_node = self.local_node.clone();
+        let identity = self.identity();
+        let network_key = self.network_key_bytes();
+        let connections = Arc::clone(&self.connections);
+        let gossip_interval = Duration::from_secs(self.config.cluster.gossip_interval.max(1));
+        let node_timeout = Duration::from_secs(self.config.cluster.node_timeout.max(1));
+
+        task::spawn(async move {
+            loop {
+                tokio::time::sleep(gossip_interval).await;
+
+                let timed_out: Vec<(Node, u64)> = {
+                    let membership_guard = membership.read().await;
+                    let nodes_guard = nodes.read().await;
+                    membership_guard
+                        .iter()
+                        .filter_map(|(id, entry)| {
+                            if entry.state == NodeState::Suspect
+                                && entry.suspected_since.is_some_and(|since| since.elapsed() >= node_timeout)
+                            {
+                                nodes_guard.get(id).map(|node| (node.clone(), entry.incarnation))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect()
+                };
+                for (node, incarnation) in timed_out {
+                    warn!("Node {} timed out as suspected, marking dead", node.id);
+                    apply_membership_update(
+                        &nodes,
+                        &health,
+                        &membership,
+                        &pending_updates,
+                        &local_node,
+                        &local_incarnation,
+                        &MembershipUpdate { node, state: NodeState::Dead, incarnation },
+                    ).await;
+                }
+
+                let candidates: Vec<Node> = nodes
+                    .read()
+                    .await
+                    .values()
+                    .filter(|node| node.id != local_node.id)
+                    .cloned()
+                    .collect();
+
+                let Some(target) = candidates.choose(&mut rand::thread_rng()).cloned() else {
+                    continue;
+                };
+
+                let outgoing_updates = pending_updates.read().await.clone();
+                let probed = tokio::time::timeout(
+                    DIRECT_PROBE_TIMEOUT,
+                    send_raw_message(
+                        &target,
+                        &ClusterMessage::Ping { updates: outgoing_updates },
+                        &identity,
+                        &network_key,
+                        &connections,
+                    ),
+                ).await;
+
+                if let Ok(Ok(reply)) = probed {
+                    health.write().await.insert(target.id.clone(), true);
+                    if let ClusterMessage::Pong { updates: incoming } = reply {
+                        for update in &incoming {
+                            apply_membership_update(
+                                &nodes,
+                                &health,
+                                &membership,
+                                &pending_updates,
+                                &local_node,
+                                &local_incarnation,
+                                update,
+                            ).await;
+                        }
+                    }
+                    continue;
+                }
+
+                warn!(
+                    "Direct probe of {} failed, suspecting it and requesting indirect probes",
+                    target.id
+                );
+                let incarnation = membership.read().await.get(&target.id).map(|e| e.incarnation).unwrap_or(0);
+                apply_membership_update(
+                    &nodes,
+                    &health,
+                    &membership,
+                    &pending_updates,
+                    &local_node,
+                    &local_incarnation,
+                    &MembershipUpdate { node: target.clone(), state: NodeState::Suspect, incarnation },
+                ).await;
+
+                let mut relays: Vec<Node> = candidates.into_iter().filter(|node| node.id != target.id).collect();
+                relays.shuffle(&mut rand::thread_rng());
+                relays.truncate(INDIRECT_PROBE_FANOUT);
+
+                for relay in relays {
+                    let relay_updates = pending_updates.read().await.clone();
+                    let _ = send_raw_message(
+                        &relay,
+                        &ClusterMessage::PingReq { target: target.clone(), updates: relay_updates },
+                        &identity,
+                        &network_key,
+                        &connections,
+                    ).await;
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Add a peer to the cluster
+    pub async fn add_peer(&self, peer: String) {
+        let mut peers = self.peers.write().await;
+        if !peers.contains(&peer) {
+            peers.push(peer);
+        }
+    }
+
+    /// Get the list of peers
+    pub async fn get_peers(&self) -> Vec<String> {
+        self.peers.read().await.clone()
+    }
+    
+    /// Get the cluster configuration
+    pub fn get_config(&self) -> &ClusterConfig {
+        &self.config
+    }
+
+    /// Get the local node's current info (including its live slot assignment)
+    pub fn get_local_node(&self) -> &Node {
+        &self.local_node
+    }
+
+    /// Open a dedicated (unpooled) connection to `node` and push `data` to
+    /// it as a `StreamChunk` sequence, e.g. a post-rebalance state transfer
+    /// too large to buffer whole in a single frame. Uses its own connection
+    /// rather than the pooled one so a slow transfer can't block ordinary
+    /// messages to the same peer.
+    pub async fn send_state_transfer(&self, node: &Node, data: &[u8]) -> ClusterResult<()> {
+        let identity = self.identity();
+        let network_key = self.network_key_bytes();
+        let stream = crate::secure_transport::Transport::connect(&node.transport_addr())
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!(
+                "Failed to connect to node {}: {}", node.id, e
+            )))?;
+        let mut channel = crate::secure_transport::client_handshake(
+            stream, &identity, &network_key, Some(node.id.as_str()), self.config.cluster.max_frame_size,
+        ).await?;
+        send_stream(&mut channel, Uuid::new_v4(), data).await
+    }
+
+    /// Open a dedicated (unpooled) connection to `node` and pull a
+    /// `StreamChunk` sequence from it, collecting the chunks into one
+    /// buffer as they arrive rather than requiring the sender to have
+    /// buffered the whole thing up front.
+    pub async fn receive_state_transfer(&self, node: &Node) -> ClusterResult<Bytes> {
+        use futures::StreamExt;
+
+        let identity = self.identity();
+        let network_key = self.network_key_bytes();
+        let stream = crate::secure_transport::Transport::connect(&node.transport_addr())
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!(
+                "Failed to connect to node {}: {}", node.id, e
+            )))?;
+        let mut channel = crate::secure_transport::client_handshake(
+            stream, &identity, &network_key, Some(node.id.as_str()), self.config.cluster.max_frame_size,
+        ).await?;
+
+        let mut collected = bytes::BytesMut::new();
+        let mut chunks = Box::pin(recv_stream(&mut channel));
+        while let Some(chunk) = chunks.next().await {
+            collected.extend_from_slice(&chunk?);
+        }
+        Ok(collected.freeze())
+    }
+
+    /// This node's long-lived ed25519 identity, used to authenticate
+    /// outgoing and incoming connections
+    fn identity(&self) -> Arc<crate::secure_transport::NodeIdentity> {
+        Arc::clone(&self.identity)
+    }
+
+    /// Raw bytes of the cluster-wide pre-shared network key, decoded from
+    /// `cluster.network_key`. An empty/invalid value decodes to no bytes,
+    /// which still lets the handshake run (ed25519 identity is still
+    /// checked) but skips the network-membership proof.
+    fn network_key_bytes(&self) -> Vec<u8> {
+        hex::decode(&self.config.cluster.network_key).unwrap_or_default()
+    }
+
+    /// Send `GetPeers` on an already-handshaked connection and merge the
+    /// `Peers` reply into this node's hash ring, node map, and node table,
+    /// letting membership propagate transitively from a single seed.
+    async fn request_peers(
+        &self,
+        channel: &mut crate::secure_transport::SecureChannel,
+        peer_addr: &str,
+    ) -> ClusterResult<()> {
+        let request = encode_envelope(BOOTSTRAP_REQUEST_ID, &ClusterMessage::GetPeers)?;
+        channel.send_frame(&request).await?;
+
+        let data = match tokio::time::timeout(Duration::from_secs(5), channel.recv_frame()).await {
+            Ok(Ok(data)) => data,
+            Ok(Err(e)) => return Err(e),
+            Err(_) => {
+                return Err(ClusterError::CommunicationError(format!(
+                    "GetPeers to {} timed out", peer_addr
                 )));
             }
         };
-        
-        // Spawn a task to handle incoming connections
+
+        match decode_envelope(&data) {
+            Ok((_, ClusterMessage::Peers { nodes: peer_nodes })) => {
+                for node in peer_nodes {
+                    if node.id == self.local_node.id {
+                        continue;
+                    }
+                    self.handle_node_joined(&node).await?;
+                    self.node_table.touch(node).await;
+                }
+                Ok(())
+            }
+            Ok((_, other)) => Err(ClusterError::CommunicationError(format!(
+                "Expected Peers from {}, got {:?}", peer_addr, other
+            ))),
+            Err(e) => Err(ClusterError::CommunicationError(format!(
+                "Failed to decode Peers from {}: {}", peer_addr, e
+            ))),
+        }
+    }
+
+    /// Fetch a key from `node` over the cluster RPC protocol: sends a
+    /// `FetchRequest`, registers a oneshot waiter keyed by its `request_id`,
+    /// and awaits the matching `FetchResponse` up to `cluster.fetch_timeout`
+    /// seconds. The waiter is removed either way, so a late response after a
+    /// timeout is just dropped rather than completing a stale receiver.
+    pub async fn fetch_remote(&self, node: &Node, key: &str) -> ClusterResult<Option<CacheEntry>> {
+        self.fetch_remote_with_headers(node, key, RequestHeaders::default()).await
+    }
+
+    /// Like `fetch_remote`, but lets the caller attach `RequestHeaders` (e.g.
+    /// a batch `sequence` number) that the responder echoes back unchanged.
+    pub async fn fetch_remote_with_headers(
+        &self,
+        node: &Node,
+        key: &str,
+        headers: RequestHeaders,
+    ) -> ClusterResult<Option<CacheEntry>> {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending_requests.write().await.insert(request_id, tx);
+
+        if let Err(e) = self
+            .send_message(node, ClusterMessage::FetchRequest { request_id, key: key.to_string(), headers })
+            .await
+        {
+            self.pending_requests.write().await.remove(&request_id);
+            return Err(e);
+        }
+
+        let timeout = Duration::from_secs(self.config.cluster.fetch_timeout.max(1));
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(_)) => Err(ClusterError::CommunicationError(format!(
+                "Fetch request {} to {} was dropped without a response", request_id, node.id
+            ))),
+            Err(_) => {
+                self.pending_requests.write().await.remove(&request_id);
+                Err(ClusterError::CommunicationError(format!(
+                    "Fetch request {} to {} timed out", request_id, node.id
+                )))
+            }
+        }
+    }
+
+    /// Ask `node` for the hash (and, if `level` is the leaf level, the full
+    /// entry list) of its Merkle tree node at (`level`, `index`), for
+    /// anti-entropy reconciliation. Used to descend a peer's tree one level
+    /// at a time, only where hashes disagree with the local tree.
+    pub async fn query_merkle_node(&self, node: &Node, level: usize, index: usize) -> ClusterResult<MerkleNodeAnswer> {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending_merkle_requests.write().await.insert(request_id, tx);
+
+        if let Err(e) = self
+            .send_message(node, ClusterMessage::MerkleNodeRequest { request_id, level, index })
+            .await
+        {
+            self.pending_merkle_requests.write().await.remove(&request_id);
+            return Err(e);
+        }
+
+        let timeout = Duration::from_secs(self.config.cluster.fetch_timeout.max(1));
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(_)) => Err(ClusterError::CommunicationError(format!(
+                "Merkle node request {} to {} was dropped without a response", request_id, node.id
+            ))),
+            Err(_) => {
+                self.pending_merkle_requests.write().await.remove(&request_id);
+                Err(ClusterError::CommunicationError(format!(
+                    "Merkle node request {} to {} timed out", request_id, node.id
+                )))
+            }
+        }
+    }
+
+    /// Join a new peer to the cluster's live node set and recompute slot
+    /// assignments. Safe to call concurrently with serving traffic.
+    pub async fn add_node(&self, node: Node) -> ClusterResult<()> {
+        info!("Adding node to cluster: {}", node.id);
+
+        {
+            let mut nodes = self.nodes.write().await;
+            nodes.insert(node.id.clone(), node);
+        }
+
+        recompute_slot_assignments(&self.nodes).await;
+        Ok(())
+    }
+
+    /// Remove a peer from the cluster's live node set and redistribute its
+    /// hash slots across the remaining nodes. Keys the departing node held
+    /// are served lazily once routing resolves them to their new owner;
+    /// this does not itself copy data off the node.
+    pub async fn remove_node(&self, node_id: &str) -> ClusterResult<()> {
+        info!("Removing node from cluster: {}", node_id);
+
+        self.nodes.write().await.remove(node_id);
+
+        recompute_slot_assignments(&self.nodes).await;
+        self.health.write().await.remove(node_id);
+        Ok(())
+    }
+
+    /// Find the node that owns a given hash slot, if slot-based sharding is configured
+    pub async fn get_node_for_slot(&self, slot: u16) -> Option<Node> {
+        let nodes = self.nodes.read().await;
+        nodes
+            .values()
+            .find(|node| node.slots.is_some_and(|range| range.contains(slot)))
+            .cloned()
+    }
+
+    /// Resolve the replica set for a key: its primary owner followed by the
+    /// next `n - 1` distinct physical nodes walking clockwise around the
+    /// consistent hash ring, skipping virtual-node duplicates of nodes
+    /// already chosen. Returns fewer than `n` nodes if the cluster is that small.
+    pub async fn get_responsible_nodes(&self, key: &str, n: usize) -> Vec<Node> {
+        let nodes = self.nodes.read().await;
+        let mut ordered = nodes_clockwise_from(&nodes, self.config.cluster.virtual_nodes, key);
+        ordered.truncate(n);
+        ordered
+    }
+
+    /// Like `get_responsible_nodes`, but resolves replicas for many keys
+    /// against a single ring built from one `nodes` read lock, instead of
+    /// re-acquiring the lock and rebuilding the virtual-node ring per key --
+    /// for batch callers like anti-entropy leaf reconciliation, which checks
+    /// replica ownership for every key in a divergent range.
+    pub async fn get_responsible_nodes_for_keys(&self, keys: &[&str], n: usize) -> HashMap<String, Vec<Node>> {
+        let nodes = self.nodes.read().await;
+        let ring = build_ring(&nodes, self.config.cluster.virtual_nodes);
+        keys.iter()
+            .map(|key| {
+                let mut ordered = walk_ring_from(&ring, key);
+                ordered.truncate(n);
+                (key.to_string(), ordered)
+            })
+            .collect()
+    }
+
+    /// Whether `node_id` and `peer_id` could ever end up in the same replica
+    /// set under the current ring: true iff, for some virtual point on the
+    /// ring, both are among the first `replication` distinct physical nodes
+    /// met walking clockwise from it. A key's walk in `get_responsible_nodes`
+    /// starts at the first virtual point at or after its hash, so these are
+    /// exactly the replica sets a key can get, and two nodes that never
+    /// appear together in one can never co-own a key.
+    /// Used to restrict anti-entropy to peers worth reconciling against at
+    /// all, rather than every healthy node in the cluster.
+    pub async fn shares_replica_set(&self, node_id: &str, peer_id: &str, replication: usize) -> bool {
+        if node_id == peer_id {
+            return false;
+        }
+
+        let nodes = self.nodes.read().await;
+        let ring = build_ring(&nodes, self.config.cluster.virtual_nodes);
+        let replication = replication.max(1);
+
+        ring.keys().any(|&point| {
+            let mut replicas: HashSet<&str> = HashSet::new();
+            for (_, node) in ring.range(point..).chain(ring.range(..point)) {
+                if replicas.len() == replication {
+                    break;
+                }
+                replicas.insert(node.id.as_str());
+            }
+            replicas.contains(node_id) && replicas.contains(peer_id)
+        })
+    }
+
+    /// Build the configured registry backend, register the local node, and
+    /// spawn a task that keeps `nodes` (and slot assignments) in sync with
+    /// membership events from the registry.
+    async fn start_registry_membership(&mut self, registry_config: RegistryConfig) -> ClusterResult<()> {
+        info!(
+            "Registry backend '{}' configured at {} ({}); using dynamic membership",
+            registry_config.backend, registry_config.endpoints, registry_config.path
+        );
+
+        let registry: Arc<dyn Registry> = match registry_config.backend.as_str() {
+            "zookeeper" => Arc::new(ZookeeperRegistry::new(
+                registry_config.endpoints.clone(),
+                registry_config.path.clone(),
+            )),
+            other => {
+                warn!("Unknown registry backend '{}', keeping the static peer list", other);
+                return Ok(());
+            }
+        };
+
+        registry.register(&self.local_node).await?;
+
+        let mut events = registry.watch().await?;
+        let nodes = Arc::clone(&self.nodes);
+
+        task::spawn(async move {
+            while let Some(event) = events.recv().await {
+                match event {
+                    MembershipEvent::NodeJoined(node) => {
+                        info!("Registry reported node joined: {}", node.id);
+                        nodes.write().await.insert(node.id.clone(), node.clone());
+                    }
+                    MembershipEvent::NodeLeft(node) => {
+                        info!("Registry reported node left: {}", node.id);
+                        nodes.write().await.remove(&node.id);
+                    }
+                }
+
+                recompute_slot_assignments(&nodes).await;
+            }
+
+            debug!("Registry membership watch closed");
+        });
+
+        self.registry = Some(registry);
+        Ok(())
+    }
+
+    /// Build the configured node-discovery backend and spawn a task that
+    /// re-polls it every `poll_interval_secs`, diffing the returned set
+    /// against what the previous poll returned and applying only the
+    /// difference to `nodes` (and slot assignments) — so a catalog response
+    /// that hasn't changed, the common case, doesn't churn anything on every
+    /// tick. The local node is always filtered out of the discovered set
+    /// first, even if the catalog happens to list it.
+    async fn start_node_discovery(&mut self, discovery_config: NodeDiscoveryConfig) -> ClusterResult<()> {
+        info!(
+            "Node-discovery backend '{}' configured at {} (service '{}'); polling every {}s",
+            discovery_config.backend,
+            discovery_config.catalog_endpoint,
+            discovery_config.service_name,
+            discovery_config.poll_interval_secs
+        );
+
+        let discovery: Arc<dyn NodeDiscovery> = match discovery_config.backend.as_str() {
+            "consul" => Arc::new(ConsulNodeDiscovery::new(
+                discovery_config.catalog_endpoint.clone(),
+                discovery_config.service_name.clone(),
+            )),
+            other => {
+                warn!("Unknown node-discovery backend '{}', keeping the static peer list", other);
+                return Ok(());
+            }
+        };
+
+        let nodes = Arc::clone(&self.nodes);
+        let local_id = self.local_node.id.clone();
+        let poll_interval = std::time::Duration::from_secs(discovery_config.poll_interval_secs.max(1));
+        let task_discovery = Arc::clone(&discovery);
+
         task::spawn(async move {
-            info!("Message listener running on {}", addr);
-            
+            // What the last successful poll discovered, so the next poll can
+            // tell whether anything actually changed before touching
+            // membership. Starts empty, so the first poll's findings are
+            // always applied as additions.
+            let mut known: HashMap<String, Node> = HashMap::new();
+
             loop {
-                match listener.accept().await {
-                    Ok((mut stream, peer_addr)) => {
-                        debug!("Accepted connection from {}", peer_addr);
-                        
-                        // Clone what we need for the handler
-                        let message_sender = message_sender.clone();
-                        let local_node_clone = local_node.clone();
-                        let nodes_clone = Arc::clone(&nodes);
-                        let hash_ring_clone = Arc::clone(&hash_ring);
-                        
-                        // Spawn a task to handle this connection
-                        task::spawn(async move {
-                            use tokio::io::{AsyncReadExt, AsyncWriteExt};
-                            
-                            // First, try to read a small amount to detect direct fetch requests
-                            let mut small_buf = [0u8; 64];  // Enough for a reasonable key name
-                            let n = match stream.read(&mut small_buf).await {
-                                Ok(n) => n,
-                                Err(e) => {
-                                    error!("Failed to read initial data from {}: {}", peer_addr, e);
-                                    return;
-                                }
-                            };
-                            
-                            // If it starts with GET:, it's a direct fetch request
-                            if n > 4 && &small_buf[0..4] == b"GET:" {
-                                let request = String::from_utf8_lossy(&small_buf[4..n]);
-                                let key = request.trim();
-                                debug!("Received direct fetch request for key: {}", key);
-                                
-                                // For now, just generate test data response
-                                // In a real implementation, we would access the local cache
-                                let response = if key.starts_with("test") {
-                                    format!("FOUND:value_for_{}", key)
-                                } else {
-                                    "NOT_FOUND".to_string()
-                                };
-                                
-                                if let Err(e) = stream.write_all(response.as_bytes()).await {
-                                    error!("Failed to send direct fetch response for key {}: {}", key, e);
-                                }
-                                
-                                if let Err(e) = stream.flush().await {
-                                    error!("Failed to flush direct fetch response: {}", e);
-                                }
-                                
-                                debug!("Sent direct fetch response for key {}: {}", key, response);
-                                return;
+                match task_discovery.discover().await {
+                    Ok(discovered) => {
+                        let current: HashMap<String, Node> = discovered
+                            .into_iter()
+                            .filter(|node| node.id != local_id)
+                            .map(|node| (node.id.clone(), node))
+                            .collect();
+
+                        if current != known {
+                            let added: Vec<Node> = current
+                                .values()
+                                .filter(|node| !known.contains_key(&node.id))
+                                .cloned()
+                                .collect();
+                            let removed: Vec<String> = known
+                                .keys()
+                                .filter(|id| !current.contains_key(*id))
+                                .cloned()
+                                .collect();
+
+                            for node in &added {
+                                info!("Node discovery: peer joined: {}", node.id);
+                                nodes.write().await.insert(node.id.clone(), node.clone());
                             }
-                            
-                            // If it's not a direct fetch, handle it as a normal message
-                            // Reset the stream position
-                            let mut full_data = small_buf[0..n].to_vec();
-                            // Read message length (4 bytes)
-                            let mut len_bytes = [0u8; 4];
-                            if let Err(e) = stream.read_exact(&mut len_bytes).await {
-                                error!("Failed to read message length from {}: {}", peer_addr, e);
-                                return;
+                            for id in &removed {
+                                info!("Node discovery: peer left: {}", id);
+                                nodes.write().await.remove(id);
                             }
-                            
-                            let len = u32::from_be_bytes(len_bytes) as usize;
-                            
-                            // Read the message data
-                            let mut data = vec![0u8; len];
-                            if let Err(e) = stream.read_exact(&mut data).await {
-                                error!("Failed to read message data from {}: {}", peer_addr, e);
-                                return;
+
+                            recompute_slot_assignments(&nodes).await;
+                            known = current;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Node-discovery poll failed: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(poll_interval).await;
+            }
+        });
+
+        self.node_discovery = Some(discovery);
+        Ok(())
+    }
+
+    /// Start a listener for incoming messages from other nodes, over
+    /// whichever transport this node advertises (`NodeAddr::Tcp` or
+    /// `NodeAddr::Unix`). Every connection must complete the authenticated
+    /// handshake before any `ClusterMessage` is accepted from it; connections
+    /// that fail it (bad signature, unknown network key, or no handshake at
+    /// all) are dropped.
+    async fn start_message_listener(&self) -> ClusterResult<()> {
+        let message_sender = self.message_sender.clone();
+        let local_node = self.local_node.clone();
+        let nodes = Arc::clone(&self.nodes);
+        let pending_updates = Arc::clone(&self.pending_updates);
+        let identity = self.identity();
+        let network_key = self.network_key_bytes();
+        let max_frame_size = self.config.cluster.max_frame_size;
+
+        match self.local_node.transport_addr() {
+            NodeAddr::Tcp(addr) => {
+                let listener = match tokio::net::TcpListener::bind(addr).await {
+                    Ok(listener) => {
+                        info!("Started message listener on {}", addr);
+                        listener
+                    }
+                    Err(e) => {
+                        return Err(ClusterError::InitializationError(format!(
+                            "Failed to start message listener on {}: {}", addr, e
+                        )));
+                    }
+                };
+
+                task::spawn(async move {
+                    info!("Message listener running on {}", addr);
+                    loop {
+                        match listener.accept().await {
+                            Ok((stream, peer_addr)) => {
+                                debug!("Accepted connection from {}", peer_addr);
+                                task::spawn(handle_incoming_connection(
+                                    crate::secure_transport::Transport::Tcp(stream),
+                                    peer_addr.to_string(),
+                                    peer_addr.ip().to_string(),
+                                    peer_addr.port(),
+                                    Arc::clone(&identity),
+                                    network_key.clone(),
+                                    max_frame_size,
+                                    message_sender.clone(),
+                                    local_node.clone(),
+                                    Arc::clone(&nodes),
+                                    Arc::clone(&pending_updates),
+                                ));
                             }
-                            
-                            // Try to deserialize as a Node first
-                            let maybe_node: Result<Node, _> = bincode::deserialize(&data);
-                            
-                            if let Ok(node) = maybe_node {
-                                // This is a node registration message
-                                info!("Received node registration from {}: {}", peer_addr, node.id);
-                                
-                                // Add this node to our hash ring by sending a node joined message
-                                if let Some(tx) = &message_sender {
-                                    // Send our node info back
-                                    let local_node_bytes = bincode::serialize(&local_node_clone).unwrap();
-                                    let len = local_node_bytes.len() as u32;
-                                    let len_bytes = len.to_be_bytes();
-                                    
-                                    if let Err(e) = stream.write_all(&len_bytes).await {
-                                        error!("Failed to send node info length: {}", e);
-                                    } else if let Err(e) = stream.write_all(&local_node_bytes).await {
-                                        error!("Failed to send node info: {}", e);
-                                    } else {
-                                        debug!("Sent node info to {}", peer_addr);
-                                    }
-                                    
-                                    // Add this node to our ring
-                                    let mut ring = hash_ring_clone.write().await;
-                                    ring.add(node.clone());
-                                    
-                                    let mut nodes = nodes_clone.write().await;
-                                    nodes.insert(node.id.clone(), node.clone());
-                                    
-                                    info!("Added node {} to hash ring", node.id);
-                                }
-                                return;
+                            Err(e) => {
+                                error!("Failed to accept connection: {}", e);
                             }
-                            
-                            // If not a node, it's a regular message
-                            let message: ClusterMessage = match bincode::deserialize(&data) {
-                                Ok(msg) => msg,
-                                Err(e) => {
-                                    error!("Failed to deserialize message from {}: {}", peer_addr, e);
-                                    return;
-                                }
-                            };
-                            
-                            debug!("Received message from {}: {:?}", peer_addr, message);
-                            
-                            // Find the sender node or create a placeholder
-                            let sender_node = {
-                                let nodes_read = nodes_clone.read().await;
-                                nodes_read.values()
-                                    .find(|n| format!("{}:{}", n.host, n.port) == peer_addr.to_string())
-                                    .cloned()
-                                    .unwrap_or_else(|| {
-                                        debug!("Unknown sender node from {}, using placeholder", peer_addr);
-                                        Node::new(
-                                            peer_addr.ip().to_string(),
-                                            peer_addr.port(),
-                                            0 // We don't know the API port
-                                        )
-                                    })
-                            };
-                            
-                            // Forward the message to our message processor
-                            if let Some(tx) = &message_sender {
-                                if let Err(e) = tx.send((sender_node, message)).await {
-                                    error!("Failed to forward message to processor: {}", e);
-                                }
+                        }
+                    }
+                });
+            }
+            NodeAddr::Unix(path) => {
+                // A stale socket file from a previous, uncleanly-terminated
+                // run would otherwise make the bind fail with "address in use".
+                let _ = std::fs::remove_file(&path);
+                let listener = match tokio::net::UnixListener::bind(&path) {
+                    Ok(listener) => {
+                        info!("Started message listener on unix:{}", path.display());
+                        listener
+                    }
+                    Err(e) => {
+                        return Err(ClusterError::InitializationError(format!(
+                            "Failed to start message listener on unix:{}: {}", path.display(), e
+                        )));
+                    }
+                };
+
+                task::spawn(async move {
+                    info!("Message listener running on unix:{}", path.display());
+                    loop {
+                        match listener.accept().await {
+                            Ok((stream, _)) => {
+                                let peer_label = format!("unix:{}", path.display());
+                                debug!("Accepted connection from {}", peer_label);
+                                task::spawn(handle_incoming_connection(
+                                    crate::secure_transport::Transport::Unix(stream),
+                                    peer_label,
+                                    path.display().to_string(),
+                                    0,
+                                    Arc::clone(&identity),
+                                    network_key.clone(),
+                                    max_frame_size,
+                                    message_sender.clone(),
+                                    local_node.clone(),
+                                    Arc::clone(&nodes),
+                                    Arc::clone(&pending_updates),
+                                ));
                             }
-                            
-                            // Send ACK
-                            if let Err(e) = stream.write_all(&[1u8]).await {
-                                error!("Failed to send ACK to {}: {}", peer_addr, e);
+                            Err(e) => {
+                                error!("Failed to accept connection: {}", e);
                             }
-                        });
-                    },
-                    Err(e) => {
-                        error!("Failed to accept connection: {}", e);
+                        }
                     }
-                }
+                });
             }
-        });
-        
+        }
+
         Ok(())
     }
 }
 
+/// Handle one accepted, not-yet-handshaked connection for as long as it
+/// stays open: complete the server side of the handshake, then repeatedly
+/// decode, process, and reply to envelopes until the peer disconnects.
+/// `peer_label` is used only for logging; `peer_host`/`peer_port` seed the
+/// placeholder `Node` used if the peer hasn't sent a `Hello` yet (for a Unix
+/// connection there's no real port, so `peer_port` is just 0).
+#[allow(clippy::too_many_arguments)]
+async fn handle_incoming_connection(
+    stream: crate::secure_transport::Transport,
+    peer_label: String,
+    peer_host: String,
+    peer_port: u16,
+    identity: Arc<crate::secure_transport::NodeIdentity>,
+    network_key: Vec<u8>,
+    max_frame_size: usize,
+    message_sender: Option<mpsc::Sender<(Node, ClusterMessage)>>,
+    local_node: Node,
+    nodes: Arc<RwLock<HashMap<String, Node>>>,
+    pending_updates: Arc<RwLock<Vec<MembershipUpdate>>>,
+) {
+    let mut channel = match crate::secure_transport::server_handshake(stream, &identity, &network_key, max_frame_size).await {
+        Ok(channel) => channel,
+        Err(e) => {
+            warn!("Rejecting connection from {}: handshake failed: {}", peer_label, e);
+            return;
+        }
+    };
+
+    info!("Completed handshake with {} ({})", peer_label, channel.peer_id());
+
+    loop {
+        let data = match channel.recv_frame().await {
+            Ok(data) => data,
+            Err(e) => {
+                debug!("Connection from {} ({}) closed: {}", peer_label, channel.peer_id(), e);
+                return;
+            }
+        };
+
+        let (request_id, message) = match decode_envelope(&data) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                error!("Failed to decode message from {}: {}", peer_label, e);
+                return;
+            }
+        };
+
+        debug!("Received message from {} ({}): {:?}", peer_label, channel.peer_id(), message);
+
+        // Find the sender node or create a placeholder; a `Hello` about to
+        // be forwarded will register the real node info a moment later.
+        let sender_node = {
+            let nodes_read = nodes.read().await;
+            nodes_read
+                .get(channel.peer_id())
+                .cloned()
+                .unwrap_or_else(|| Node::with_id(channel.peer_id().to_string(), peer_host.clone(), peer_port, 0))
+        };
+
+        // A Hello is replied to with our own Hello (so a freshly dialing
+        // seed learns our node info in the same round trip), GetPeers is
+        // replied to with our known-node list (so the cluster grows
+        // transitively from one seed), a Ping is replied to with our own
+        // piggybacked updates (so gossip converges on both legs of a probe,
+        // not just the outgoing one); every other message just gets a
+        // generic Ack once handed to the processor.
+        let reply = match &message {
+            ClusterMessage::Hello { node } => {
+                {
+                    let mut nodes = nodes.write().await;
+                    nodes.insert(node.id.clone(), node.clone());
+                }
+                recompute_slot_assignments(&nodes).await;
+                ClusterMessage::Hello { node: local_node.clone() }
+            }
+            ClusterMessage::GetPeers => {
+                let mut known: Vec<Node> = nodes.read().await.values().cloned().collect();
+                if !known.iter().any(|n| n.id == local_node.id) {
+                    known.push(local_node.clone());
+                }
+                ClusterMessage::Peers { nodes: known }
+            }
+            ClusterMessage::Ping { .. } => {
+                let updates = pending_updates.read().await.clone();
+                ClusterMessage::Pong { updates }
+            }
+            _ => ClusterMessage::Ack,
+        };
+
+        if let Some(tx) = &message_sender {
+            if let Err(e) = tx.send((sender_node, message)).await {
+                error!("Failed to forward message to processor: {}", e);
+            }
+        }
+
+        let reply_bytes = match encode_envelope(request_id, &reply) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!("Failed to encode reply: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = channel.send_frame(&reply_bytes).await {
+            error!("Failed to send reply to {}: {}", peer_label, e);
+            return;
+        }
+    }
+}
+
 #[async_trait]
 impl ClusterNode for FastbuCluster {
     async fn initialize(&mut self) -> ClusterResult<()> {
         info!("Initializing cluster node with ID: {}", self.local_node.id);
+        if self.config.cluster.network_key.is_empty() {
+            warn!(
+                "cluster.network_key is not set; connections are authenticated by ed25519 \
+                 identity only, with no proof of cluster membership. Fine for local \
+                 development, not recommended for a network-exposed deployment."
+            );
+        }
         // Start the message processor
         self.start_message_processor().await?;
         // Start the message listener
         self.start_message_listener().await?;
+        // Start the SWIM failure detector
+        self.start_failure_detector().await?;
+
+        // Self-discover our publicly-reachable address, for nodes behind
+        // NAT or in containers where the configured `host` is wrong.
+        if let Some(ip_echo_port) = self.config.cluster.ip_echo_port {
+            let echo_bind = SocketAddr::new(self.local_node.addr().ip(), ip_echo_port);
+            if let Err(e) = crate::ip_echo::start_ip_echo_server(echo_bind).await {
+                warn!("Failed to start ip-echo server on {}: {}", echo_bind, e);
+            }
+
+            if let Some(first_seed) = self.config.cluster.seeds.first() {
+                let seed_host = first_seed.split(':').next().unwrap_or(first_seed);
+                let echo_addr = format!("{}:{}", seed_host, ip_echo_port);
+                match crate::ip_echo::query_ip_echo(
+                    &echo_addr,
+                    vec![self.local_node.port, self.local_node.api_port],
+                ).await {
+                    Ok(response) => {
+                        let observed_host = response.address.to_string();
+                        if observed_host != self.local_node.host {
+                            info!(
+                                "Correcting advertised host from {} to {} (observed by ip-echo at {})",
+                                self.local_node.host, observed_host, echo_addr
+                            );
+                            self.local_node.host = observed_host;
+                        }
+
+                        for port in [self.local_node.port, self.local_node.api_port] {
+                            if !response.reachable_ports.contains(&port) {
+                                warn!(
+                                    "Advertised address {}:{} does not appear reachable from {}; \
+                                     peers behind a different NAT or firewall may not be able to \
+                                     reach this node",
+                                    self.local_node.host, port, echo_addr
+                                );
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!("ip-echo self-discovery against {} failed: {}", echo_addr, e);
+                    }
+                }
+            }
+        }
 
         // Add ourselves to the nodes list
         {
@@ -578,87 +2656,109 @@ impl ClusterNode for FastbuCluster {
             self.add_peer(seed.clone()).await;
         }
 
+        // Also retry peers recorded in the on-disk node table from a
+        // previous run, favoring the ones seen most recently, so the
+        // cluster doesn't depend solely on the static seed list surviving.
+        for addr in self.node_table.addresses().await {
+            self.add_peer(addr).await;
+        }
+
         // Try to connect to each peer and exchange node info
         let peers = self.get_peers().await;
         let mut any_success = false;
         
+        let identity = self.identity();
+        let network_key = self.network_key_bytes();
+
         for peer_addr in peers {
-            match tokio::time::timeout(
+            let stream = match tokio::time::timeout(
                 std::time::Duration::from_secs(5), // 5 second timeout
                 tokio::net::TcpStream::connect(&peer_addr)
             ).await {
-                Ok(Ok(mut stream)) => {
-                    // Send our node info
-                    let node_bytes = match bincode::serialize(&self.local_node) {
-                        Ok(bytes) => bytes,
-                        Err(e) => {
-                            warn!("Failed to serialize node info: {}", e);
-                            continue;
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    warn!("Could not connect to peer {}: {}", peer_addr, e);
+                    continue;
+                },
+                Err(_) => {
+                    warn!("Connection to peer {} timed out", peer_addr);
+                    continue;
+                }
+            };
+
+            // We don't know the seed's node id yet, so we can't pin it down
+            // to an expected peer id; the handshake still authenticates it
+            // against its own claimed ed25519 key and the network key.
+            let mut channel = match tokio::time::timeout(
+                std::time::Duration::from_secs(5),
+                crate::secure_transport::client_handshake(
+                    crate::secure_transport::Transport::Tcp(stream),
+                    &identity,
+                    &network_key,
+                    None,
+                    self.config.cluster.max_frame_size,
+                ),
+            ).await {
+                Ok(Ok(channel)) => channel,
+                Ok(Err(e)) => {
+                    warn!("Handshake with seed {} failed: {}", peer_addr, e);
+                    continue;
+                },
+                Err(_) => {
+                    warn!("Handshake with seed {} timed out", peer_addr);
+                    continue;
+                }
+            };
+
+            let hello = ClusterMessage::Hello { node: self.local_node.clone() };
+            let hello_bytes = match encode_envelope(BOOTSTRAP_REQUEST_ID, &hello) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warn!("Failed to encode Hello for {}: {}", peer_addr, e);
+                    continue;
+                }
+            };
+            if let Err(e) = channel.send_frame(&hello_bytes).await {
+                warn!("Failed to send Hello to {}: {}", peer_addr, e);
+                continue;
+            }
+
+            match tokio::time::timeout(std::time::Duration::from_secs(5), channel.recv_frame()).await {
+                Ok(Ok(data)) => match decode_envelope(&data) {
+                    Ok((_, ClusterMessage::Hello { node: peer_node })) => {
+                        info!("Received Hello from peer {}: {}", peer_addr, peer_node.id);
+                        let peer_id = peer_node.id.clone();
+                        if let Err(e) = self.handle_node_joined(&peer_node).await {
+                            warn!("Failed to add peer node to hash ring: {}", e);
                         }
-                    };
-                    
-                    let len = node_bytes.len() as u32;
-                    let len_bytes = len.to_be_bytes();
-                    
-                    // Write length and node data with proper error handling
-                    if let Err(e) = stream.write_all(&len_bytes).await {
-                        warn!("Failed to send length bytes to {}: {}", peer_addr, e);
-                        continue;
+                        self.node_table.touch(peer_node).await;
+                        any_success = true;
+                        info!("Connected to peer {} and exchanged node info", peer_addr);
+
+                        // Ask this peer for its own known-node list too, so the
+                        // cluster grows transitively from a single seed instead
+                        // of every node needing every other node's address.
+                        if let Err(e) = self.request_peers(&mut channel, &peer_addr).await {
+                            warn!("Failed to exchange GetPeers with {}: {}", peer_addr, e);
+                        }
+
+                        // Hand the already-handshaked connection to the pool
+                        // so later sends to this peer (SWIM probes, replicated
+                        // writes, ...) reuse it instead of dialing again.
+                        adopt_connection(&self.connections, &peer_id, channel).await;
                     }
-                    
-                    if let Err(e) = stream.write_all(&node_bytes).await {
-                        warn!("Failed to send node data to {}: {}", peer_addr, e);
-                        continue;
+                    Ok((_, other)) => {
+                        warn!("Expected Hello from seed {}, got {:?}", peer_addr, other);
                     }
-                    
-                    // Read peer's node info with timeout
-                    match tokio::time::timeout(
-                        std::time::Duration::from_secs(5),
-                        async {
-                            // Read length
-                            let mut len_bytes = [0u8; 4];
-                            if let Err(e) = stream.read_exact(&mut len_bytes).await {
-                                return Err(format!("Failed to read length bytes: {}", e));
-                            }
-                            
-                            let len = u32::from_be_bytes(len_bytes) as usize;
-                            let mut data = vec![0u8; len];
-                            
-                            // Read data
-                            if let Err(e) = stream.read_exact(&mut data).await {
-                                return Err(format!("Failed to read data: {}", e));
-                            }
-                            
-                            // Deserialize
-                            match bincode::deserialize::<Node>(&data) {
-                                Ok(peer_node) => Ok(peer_node),
-                                Err(e) => Err(format!("Failed to deserialize node: {}", e))
-                            }
-                        }
-                    ).await {
-                        Ok(Ok(peer_node)) => {
-                            info!("Received node info from peer {}: {}", peer_addr, peer_node.id);
-                            // Add this node to our hash ring
-                            if let Err(e) = self.handle_node_joined(&peer_node).await {
-                                warn!("Failed to add peer node to hash ring: {}", e);
-                                // Continue anyway
-                            }
-                            any_success = true;
-                            info!("Connected to peer {} and exchanged node info", peer_addr);
-                        },
-                        Ok(Err(e)) => {
-                            warn!("Error reading from peer {}: {}", peer_addr, e);
-                        },
-                        Err(_) => {
-                            warn!("Timed out reading from peer {}", peer_addr);
-                        }
+                    Err(e) => {
+                        warn!("Failed to decode Hello from {}: {}", peer_addr, e);
                     }
                 },
                 Ok(Err(e)) => {
-                    warn!("Could not connect to peer {}: {}", peer_addr, e);
+                    warn!("Error reading Hello from peer {}: {}", peer_addr, e);
                 },
                 Err(_) => {
-                    warn!("Connection to peer {} timed out", peer_addr);
+                    warn!("Timed out reading Hello from peer {}", peer_addr);
                 }
             }
         }
@@ -667,7 +2767,18 @@ impl ClusterNode for FastbuCluster {
         if self.config.cluster.seeds.is_empty() {
             any_success = true;
         }
-        
+
+        // If a [registry] section is configured, use it for dynamic membership
+        // instead of (or alongside) the static seed list above.
+        if let Some(registry_config) = self.config.registry.clone() {
+            self.start_registry_membership(registry_config).await?;
+        }
+
+        // If a [discovery] section is configured, poll it for membership too.
+        if let Some(discovery_config) = self.config.discovery.clone() {
+            self.start_node_discovery(discovery_config).await?;
+        }
+
         // Continue with initialization even if we couldn't connect to peers
         // This allows the node to start its API server and try again later
         info!("Cluster initialization complete");
@@ -675,9 +2786,13 @@ impl ClusterNode for FastbuCluster {
     }
     
     async fn get_responsible_node(&self, key: &str) -> Option<Node> {
-        let ring = self.hash_ring.read().await;
-        // Convert the &str to a String for hashing compatibility
-        ring.get(&key.to_string()).cloned()
+        // The primary is just the first entry of the same replica-ordered
+        // ring `get_responsible_nodes` walks, so routing and replication
+        // always agree on who owns a key.
+        let nodes = self.nodes.read().await;
+        nodes_clockwise_from(&nodes, self.config.cluster.virtual_nodes, key)
+            .into_iter()
+            .next()
     }
     
     async fn get_nodes(&self) -> Vec<Node> {
@@ -685,66 +2800,30 @@ impl ClusterNode for FastbuCluster {
         nodes.values().cloned().collect()
     }
     
-    async fn send_message(&self, node: &Node, message: ClusterMessage) -> ClusterResult<()> {
+    async fn send_message(&self, node: &Node, message: ClusterMessage) -> ClusterResult<ClusterMessage> {
         debug!("Sending message to node {}: {:?}", node.id, message);
-        
-        // Skip if sending to ourselves (handled internally)
+
+        // Skip if sending to ourselves (handled internally); there's no real
+        // reply to a message we process in-process, so synthesize an Ack.
         if node.id == self.local_node.id {
             debug!("Message is for local node, processing internally");
-            return self.process_message(node, message).await;
+            self.process_message(node, message).await?;
+            return Ok(ClusterMessage::Ack);
         }
-        
-        // Serialize the message
-        let serialized = match bincode::serialize(&message) {
-            Ok(data) => data,
-            Err(e) => {
-                return Err(ClusterError::CommunicationError(format!(
-                    "Failed to serialize message: {}", e
-                )));
+
+        let identity = self.identity();
+        let network_key = self.network_key_bytes();
+        match send_raw_message(node, &message, &identity, &network_key, &self.connections).await {
+            Ok(reply) => {
+                self.mark_healthy(&node.id).await;
+                debug!("Message sent successfully to node {}", node.id);
+                Ok(reply)
             }
-        };
-        
-        // For now, we'll use a simple TCP connection to send messages
-        // In a production system, you might want to use a more robust protocol
-        let addr = node.addr();
-        
-        // Connect to the node
-        let mut stream = match tokio::net::TcpStream::connect(addr).await {
-            Ok(stream) => stream,
             Err(e) => {
-                return Err(ClusterError::CommunicationError(format!(
-                    "Failed to connect to node {}: {}", node.id, e
-                )));
+                self.mark_unhealthy(&node.id).await;
+                Err(e)
             }
-        };
-        
-        // Send the message length first (4 bytes)
-        let len = serialized.len() as u32;
-        let len_bytes = len.to_be_bytes();
-        
-        if let Err(e) = stream.write_all(&len_bytes).await {
-            return Err(ClusterError::CommunicationError(format!(
-                "Failed to send message length: {}", e
-            )));
-        }
-        
-        // Send the message data
-        if let Err(e) = stream.write_all(&serialized).await {
-            return Err(ClusterError::CommunicationError(format!(
-                "Failed to send message data: {}", e
-            )));
-        }
-        
-        // Wait for ACK (simple 1-byte response)
-        let mut response = [0u8; 1];
-        if let Err(e) = stream.read_exact(&mut response).await {
-            return Err(ClusterError::CommunicationError(format!(
-                "Failed to receive acknowledgment: {}", e
-            )));
         }
-        
-        debug!("Message sent successfully to node {}", node.id);
-        Ok(())
     }
     
     async fn process_message(&self, sender: &Node, message: ClusterMessage) -> ClusterResult<()> {
@@ -758,41 +2837,261 @@ impl ClusterNode for FastbuCluster {
     
     async fn handle_node_joined(&self, node: &Node) -> ClusterResult<()> {
         info!("Node joined: {}", node.id);
-        
-        // Add the node to our hash ring
-        {
-            let mut ring = self.hash_ring.write().await;
-            ring.add(node.clone());
-        }
-        
+
         // Add the node to our nodes list
         {
             let mut nodes = self.nodes.write().await;
             nodes.insert(node.id.clone(), node.clone());
         }
-        
+        recompute_slot_assignments(&self.nodes).await;
+
         Ok(())
     }
-    
+
     async fn handle_node_left(&self, node: &Node) -> ClusterResult<()> {
         info!("Node left: {}", node.id);
-        
-        // Remove the node from our hash ring
-        {
-            let mut ring = self.hash_ring.write().await;
-            ring.remove(node);
-        }
-        
+
         // Remove the node from our nodes list
         {
             let mut nodes = self.nodes.write().await;
             nodes.remove(&node.id);
         }
-        
+
         Ok(())
     }
 }
 
+/// Recompute contiguous, evenly-sized hash slot ranges across the known
+/// nodes (sorted by ID for determinism) and store them back onto each node.
+/// Called whenever membership changes so slot-based routing in
+/// `cluster_cache` stays consistent with the current peer set.
+async fn recompute_slot_assignments(nodes: &Arc<RwLock<HashMap<String, Node>>>) {
+    let mut nodes = nodes.write().await;
+    let mut ids: Vec<String> = nodes.keys().cloned().collect();
+    ids.sort();
+
+    let node_count = ids.len() as u16;
+    if node_count == 0 {
+        return;
+    }
+
+    let slot_count = crate::cluster_cache::SLOT_COUNT;
+    let slots_per_node = slot_count / node_count;
+    let mut start = 0u16;
+
+    for (i, id) in ids.iter().enumerate() {
+        let end = if i as u16 == node_count - 1 {
+            slot_count - 1
+        } else {
+            start + slots_per_node - 1
+        };
+
+        if let Some(node) = nodes.get_mut(id) {
+            node.slots = Some(SlotRange::new(start, end));
+        }
+
+        start = end + 1;
+    }
+
+    info!("Recomputed slot assignments across {} node(s)", node_count);
+}
+
+/// Stable (non-randomized) hash of a string, used to place replica points on
+/// the virtual ring below. `DefaultHasher`'s output is only stable within a
+/// single process, which is fine here since every node computes its own copy
+/// of the ring from the same `nodes` map and only needs internal consistency.
+fn ring_point(value: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Build a virtual-node ring: `virtual_nodes` replica points per physical
+/// node, hashed onto a `u64` circle. Factored out of `nodes_clockwise_from`
+/// so callers resolving many keys against the same membership snapshot (e.g.
+/// `get_responsible_nodes_for_keys`) can build it once and walk it per key,
+/// instead of rebuilding an identical ring on every lookup.
+fn build_ring(nodes: &HashMap<String, Node>, virtual_nodes: usize) -> BTreeMap<u64, &Node> {
+    let mut ring = BTreeMap::new();
+    for node in nodes.values() {
+        for i in 0..virtual_nodes.max(1) {
+            ring.insert(ring_point(&format!("{}#{}", node.id, i)), node);
+        }
+    }
+    ring
+}
+
+/// Walk `ring` clockwise from `key`'s hash, returning distinct physical nodes
+/// in the order their nearest virtual point is encountered.
+fn walk_ring_from(ring: &BTreeMap<u64, &Node>, key: &str) -> Vec<Node> {
+    let key_point = ring_point(key);
+    let mut seen = HashSet::new();
+    let mut ordered = Vec::new();
+
+    for (_, node) in ring.range(key_point..).chain(ring.range(..key_point)) {
+        if seen.insert(node.id.clone()) {
+            ordered.push((*node).clone());
+        }
+    }
+
+    ordered
+}
+
+/// Build a virtual-node ring (`virtual_nodes` replica points per physical
+/// node) and walk it clockwise from `key`'s hash, returning distinct physical
+/// nodes in the order their nearest virtual point is encountered. Used by
+/// `get_responsible_nodes` to pick a key's primary owner plus its replicas.
+fn nodes_clockwise_from(nodes: &HashMap<String, Node>, virtual_nodes: usize, key: &str) -> Vec<Node> {
+    let ring = build_ring(nodes, virtual_nodes);
+    walk_ring_from(&ring, key)
+}
+
+/// Send `message` to `node` over its pooled connection (dialing and
+/// handshaking a fresh one if `pool` doesn't already have one open),
+/// returning the peer's typed reply. Concurrent calls against the same peer
+/// share one connection: each gets its own request id up front, and the
+/// connection's background reader task (`read_responses`) demultiplexes
+/// replies back to the right caller as they arrive, so one slow reply
+/// doesn't block any other in-flight send to the same peer. Factored out of
+/// `FastbuCluster::send_message` so the detached message-processor and
+/// failure-detector tasks (which only hold clones of shared state, not
+/// `&self`) can send replies and probes through the same pool.
+async fn send_raw_message(
+    node: &Node,
+    message: &ClusterMessage,
+    identity: &crate::secure_transport::NodeIdentity,
+    network_key: &[u8],
+    pool: &Arc<ConnectionPool>,
+) -> ClusterResult<ClusterMessage> {
+    let conn = get_or_connect(pool, node, identity, network_key).await?;
+
+    let request_id = conn.next_request_id.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = oneshot::channel();
+    conn.pending.write().await.insert(request_id, tx);
+
+    let framed = encode_envelope(request_id, message)?;
+    if let Err(e) = conn.writer.lock().await.send_frame(&framed).await {
+        conn.pending.write().await.remove(&request_id);
+        pool.bump_backoff(&node.id).await;
+        return Err(e);
+    }
+
+    match tokio::time::timeout(RPC_REPLY_TIMEOUT, rx).await {
+        Ok(Ok(reply)) => {
+            pool.clear_backoff(&node.id).await;
+            Ok(reply)
+        }
+        Ok(Err(_)) => Err(ClusterError::CommunicationError(format!(
+            "Connection to {} closed before a reply to request {} arrived", node.id, request_id
+        ))),
+        Err(_) => {
+            conn.pending.write().await.remove(&request_id);
+            Err(ClusterError::CommunicationError(format!(
+                "Request {} to {} timed out waiting for a reply", request_id, node.id
+            )))
+        }
+    }
+}
+
+/// Record a membership fact in `pending_updates` so it gets piggybacked on
+/// future outgoing messages, replacing any older fact about the same node
+/// and dropping the oldest entries once the cap is exceeded.
+async fn queue_update(
+    membership: &Arc<RwLock<HashMap<String, MembershipEntry>>>,
+    pending_updates: &Arc<RwLock<Vec<MembershipUpdate>>>,
+    update: &MembershipUpdate,
+) {
+    membership.write().await.insert(
+        update.node.id.clone(),
+        MembershipEntry {
+            state: update.state,
+            incarnation: update.incarnation,
+            suspected_since: if update.state == NodeState::Suspect {
+                Some(Instant::now())
+            } else {
+                None
+            },
+        },
+    );
+
+    let mut pending = pending_updates.write().await;
+    pending.retain(|u| u.node.id != update.node.id);
+    pending.push(update.clone());
+    if pending.len() > MAX_PIGGYBACKED_UPDATES {
+        let excess = pending.len() - MAX_PIGGYBACKED_UPDATES;
+        pending.drain(0..excess);
+    }
+}
+
+/// Apply an incoming membership fact if it's newer or more severe than what
+/// we currently believe (see `should_apply_update`), updating `nodes` /
+/// `health` to match and queuing it for further gossip. If the update
+/// targets the local node itself, refute it instead: bump our own
+/// incarnation and re-assert `Alive`, so the false rumor gets overwritten as
+/// it continues to spread.
+#[allow(clippy::too_many_arguments)]
+async fn apply_membership_update(
+    nodes: &Arc<RwLock<HashMap<String, Node>>>,
+    health: &Arc<RwLock<HashMap<String, bool>>>,
+    membership: &Arc<RwLock<HashMap<String, MembershipEntry>>>,
+    pending_updates: &Arc<RwLock<Vec<MembershipUpdate>>>,
+    local_node: &Node,
+    local_incarnation: &Arc<AtomicU64>,
+    update: &MembershipUpdate,
+) {
+    if update.node.id == local_node.id && update.state != NodeState::Alive {
+        let new_incarnation = local_incarnation.fetch_add(1, Ordering::SeqCst) + 1;
+        info!(
+            "Refuting suspicion about ourselves, bumping incarnation to {}",
+            new_incarnation
+        );
+        let refutation = MembershipUpdate {
+            node: local_node.clone(),
+            state: NodeState::Alive,
+            incarnation: new_incarnation,
+        };
+        queue_update(membership, pending_updates, &refutation).await;
+        return;
+    }
+
+    let should_apply = {
+        let guard = membership.read().await;
+        should_apply_update(guard.get(&update.node.id), update.state, update.incarnation)
+    };
+    if !should_apply {
+        return;
+    }
+
+    queue_update(membership, pending_updates, update).await;
+
+    match update.state {
+        NodeState::Dead => {
+            nodes.write().await.remove(&update.node.id);
+            health.write().await.remove(&update.node.id);
+            recompute_slot_assignments(nodes).await;
+        }
+        NodeState::Alive => {
+            let is_new = {
+                let mut nodes_guard = nodes.write().await;
+                if nodes_guard.contains_key(&update.node.id) {
+                    false
+                } else {
+                    nodes_guard.insert(update.node.id.clone(), update.node.clone());
+                    true
+                }
+            };
+            if is_new {
+                recompute_slot_assignments(nodes).await;
+            }
+            health.write().await.insert(update.node.id.clone(), true);
+        }
+        NodeState::Suspect => {
+            // Still routable; `start_failure_detector` times it out to
+            // `Dead` if no refutation arrives before `node_timeout`.
+        }
+    }
+}
+
 // Implementation for loading cluster configuration from a file
 pub fn load_cluster_config(config_path: &str) -> Result<ClusterConfig, config::ConfigError> {
     // Read the TOML file
@@ -860,6 +3159,14 @@ mod tests {
         assert_eq!(addr.port(), 8001);
     }
     
+    #[test]
+    fn test_slot_range_contains() {
+        let range = SlotRange::new(0, 5460);
+        assert!(range.contains(0));
+        assert!(range.contains(5460));
+        assert!(!range.contains(5461));
+    }
+
     #[tokio::test]
     async fn test_cluster_config_default() {
         let config = ClusterConfig::default();
@@ -869,4 +3176,304 @@ mod tests {
         assert_eq!(config.node.host, "127.0.0.1");
         assert!(config.node.port > 0, "Port should be set");
     }
+
+    #[tokio::test]
+    async fn test_recompute_slot_assignments_splits_evenly() {
+        let mut map = HashMap::new();
+        map.insert("node-a".to_string(), Node::with_id("node-a".to_string(), "127.0.0.1".to_string(), 7001, 3001));
+        map.insert("node-b".to_string(), Node::with_id("node-b".to_string(), "127.0.0.1".to_string(), 7002, 3002));
+        let nodes = Arc::new(RwLock::new(map));
+
+        recompute_slot_assignments(&nodes).await;
+
+        let nodes = nodes.read().await;
+        let a = nodes.get("node-a").unwrap().slots.unwrap();
+        let b = nodes.get("node-b").unwrap().slots.unwrap();
+
+        // The two ranges should partition the full slot space with no gaps or overlap.
+        assert_eq!(a.start, 0);
+        assert_eq!(b.end, crate::cluster_cache::SLOT_COUNT - 1);
+        assert_eq!(a.end + 1, b.start);
+    }
+
+    #[test]
+    fn test_should_apply_update_higher_incarnation_wins() {
+        let existing = MembershipEntry {
+            state: NodeState::Alive,
+            incarnation: 1,
+            suspected_since: None,
+        };
+        assert!(should_apply_update(Some(&existing), NodeState::Suspect, 2));
+        assert!(!should_apply_update(Some(&existing), NodeState::Dead, 0));
+    }
+
+    #[test]
+    fn test_should_apply_update_equal_incarnation_prefers_more_dead() {
+        let existing = MembershipEntry {
+            state: NodeState::Suspect,
+            incarnation: 5,
+            suspected_since: None,
+        };
+        assert!(should_apply_update(Some(&existing), NodeState::Dead, 5));
+        assert!(!should_apply_update(Some(&existing), NodeState::Alive, 5));
+    }
+
+    #[test]
+    fn test_should_apply_update_no_existing_entry_always_applies() {
+        assert!(should_apply_update(None, NodeState::Alive, 0));
+    }
+
+    #[tokio::test]
+    async fn test_apply_membership_update_marks_node_dead() {
+        let node = Node::with_id("node-a".to_string(), "127.0.0.1".to_string(), 7001, 3001);
+        let local_node = Node::with_id("local".to_string(), "127.0.0.1".to_string(), 7000, 3000);
+
+        let nodes = Arc::new(RwLock::new(HashMap::from([(node.id.clone(), node.clone())])));
+        let health = Arc::new(RwLock::new(HashMap::new()));
+        let membership = Arc::new(RwLock::new(HashMap::new()));
+        let pending_updates = Arc::new(RwLock::new(Vec::new()));
+        let local_incarnation = Arc::new(AtomicU64::new(0));
+
+        apply_membership_update(
+            &nodes,
+            &health,
+            &membership,
+            &pending_updates,
+            &local_node,
+            &local_incarnation,
+            &MembershipUpdate { node: node.clone(), state: NodeState::Dead, incarnation: 1 },
+        ).await;
+
+        assert!(!nodes.read().await.contains_key(&node.id));
+        assert_eq!(pending_updates.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_apply_membership_update_self_refutes_suspicion() {
+        let local_node = Node::with_id("local".to_string(), "127.0.0.1".to_string(), 7000, 3000);
+
+        let nodes = Arc::new(RwLock::new(HashMap::new()));
+        let health = Arc::new(RwLock::new(HashMap::new()));
+        let membership = Arc::new(RwLock::new(HashMap::new()));
+        let pending_updates = Arc::new(RwLock::new(Vec::new()));
+        let local_incarnation = Arc::new(AtomicU64::new(0));
+
+        apply_membership_update(
+            &nodes,
+            &health,
+            &membership,
+            &pending_updates,
+            &local_node,
+            &local_incarnation,
+            &MembershipUpdate { node: local_node.clone(), state: NodeState::Suspect, incarnation: 0 },
+        ).await;
+
+        assert_eq!(local_incarnation.load(Ordering::SeqCst), 1);
+        let pending = pending_updates.read().await;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].state, NodeState::Alive);
+        assert_eq!(pending[0].incarnation, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_remote_fails_against_unreachable_node() {
+        let mut config = ClusterConfig::default();
+        config.cluster.fetch_timeout = 1;
+        let cluster = FastbuCluster::new(config);
+
+        // Nothing is listening on this port, so sending the request fails;
+        // fetch_remote must return that error and drop its waiter rather
+        // than hang.
+        let unreachable = Node::with_id("ghost".to_string(), "127.0.0.1".to_string(), 1, 1);
+        let result = cluster.fetch_remote(&unreachable, "some-key").await;
+        assert!(result.is_err());
+        assert!(cluster.pending_requests.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_responsible_nodes_returns_distinct_nodes_up_to_n() {
+        let mut config = ClusterConfig::default();
+        config.cluster.replication = 2;
+        let cluster = FastbuCluster::new(config);
+
+        for id in ["node-a", "node-b", "node-c"] {
+            cluster
+                .add_node(Node::with_id(id.to_string(), "127.0.0.1".to_string(), 7000, 3000))
+                .await
+                .unwrap();
+        }
+
+        let replicas = cluster.get_responsible_nodes("some-key", 2).await;
+        assert_eq!(replicas.len(), 2);
+        assert_ne!(replicas[0].id, replicas[1].id);
+    }
+
+    #[tokio::test]
+    async fn test_get_responsible_nodes_caps_at_cluster_size() {
+        let config = ClusterConfig::default();
+        let cluster = FastbuCluster::new(config);
+        cluster
+            .add_node(Node::with_id("node-a".to_string(), "127.0.0.1".to_string(), 7000, 3000))
+            .await
+            .unwrap();
+
+        // Only one node is known, so asking for 3 replicas yields just 1.
+        let replicas = cluster.get_responsible_nodes("some-key", 3).await;
+        assert_eq!(replicas.len(), 1);
+    }
+
+    fn node_table_test_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("fastbu_test_node_table_{}_{}.bin", name, std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_node_table_touch_persists_and_reloads() {
+        let path = node_table_test_path("persists");
+        let _ = std::fs::remove_file(&path);
+
+        let table = NodeTable::load(&path, 3600);
+        table.touch(Node::with_id("node-a".to_string(), "127.0.0.1".to_string(), 7001, 3001)).await;
+
+        let reloaded = NodeTable::load(&path, 3600);
+        assert_eq!(reloaded.addresses().await, vec!["127.0.0.1:7001".to_string()]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_node_table_orders_most_recently_seen_first() {
+        let path = node_table_test_path("ordering");
+        let _ = std::fs::remove_file(&path);
+
+        let table = NodeTable::load(&path, 3600);
+        table.touch(Node::with_id("node-a".to_string(), "127.0.0.1".to_string(), 7001, 3001)).await;
+        table.touch(Node::with_id("node-b".to_string(), "127.0.0.1".to_string(), 7002, 3002)).await;
+        // Re-touching node-a should move it back to the front.
+        table.touch(Node::with_id("node-a".to_string(), "127.0.0.1".to_string(), 7001, 3001)).await;
+
+        assert_eq!(
+            table.addresses().await,
+            vec!["127.0.0.1:7001".to_string(), "127.0.0.1:7002".to_string()]
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_prune_and_sort_drops_stale_entries() {
+        let fresh = NodeTableEntry {
+            node: Node::with_id("fresh".to_string(), "127.0.0.1".to_string(), 7001, 3001),
+            last_seen: Utc::now(),
+        };
+        let stale = NodeTableEntry {
+            node: Node::with_id("stale".to_string(), "127.0.0.1".to_string(), 7002, 3002),
+            last_seen: Utc::now() - chrono::Duration::seconds(120),
+        };
+        let mut entries = vec![stale, fresh];
+
+        prune_and_sort(&mut entries, 60);
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].node.id, "fresh");
+    }
+
+    #[tokio::test]
+    async fn test_connection_pool_backs_off_after_failed_connect() {
+        let pool = Arc::new(ConnectionPool::new(16 * 1024 * 1024));
+        let identity = crate::secure_transport::NodeIdentity::generate();
+        // Nothing is listening here, so the dial itself fails.
+        let unreachable = Node::with_id("ghost".to_string(), "127.0.0.1".to_string(), 1, 1);
+
+        assert!(get_or_connect(&pool, &unreachable, &identity, &[]).await.is_err());
+
+        let backoff = pool.backoff.read().await;
+        let entry = backoff.get(&unreachable.id).expect("a failed connect should record backoff");
+        assert_eq!(entry.consecutive_failures, 1);
+        assert!(entry.not_before > Instant::now());
+    }
+
+    #[tokio::test]
+    async fn test_connection_pool_rejects_reconnect_during_backoff() {
+        let pool = Arc::new(ConnectionPool::new(16 * 1024 * 1024));
+        let identity = crate::secure_transport::NodeIdentity::generate();
+        let unreachable = Node::with_id("ghost".to_string(), "127.0.0.1".to_string(), 1, 1);
+
+        assert!(get_or_connect(&pool, &unreachable, &identity, &[]).await.is_err());
+        // The second attempt should be turned away by backoff rather than
+        // trying (and failing) to dial again.
+        assert!(get_or_connect(&pool, &unreachable, &identity, &[]).await.is_err());
+        assert_eq!(
+            pool.backoff.read().await.get(&unreachable.id).unwrap().consecutive_failures,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_stream_and_recv_stream_roundtrip_large_payload() {
+        use futures::StreamExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server_identity = crate::secure_transport::NodeIdentity::generate();
+        let client_identity = crate::secure_transport::NodeIdentity::generate();
+
+        // Bigger than one STREAM_CHUNK_SIZE chunk, so the roundtrip actually
+        // exercises more than one `StreamChunk` frame.
+        let payload: Vec<u8> = (0..(STREAM_CHUNK_SIZE * 2 + 17)).map(|i| (i % 251) as u8).collect();
+        let expected = payload.clone();
+
+        let server_task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut channel = crate::secure_transport::server_handshake(
+                crate::secure_transport::Transport::Tcp(stream), &server_identity, b"", 16 * 1024 * 1024,
+            ).await.unwrap();
+
+            let mut collected = Vec::new();
+            let mut chunks = Box::pin(recv_stream(&mut channel));
+            while let Some(chunk) = chunks.next().await {
+                collected.extend_from_slice(&chunk.unwrap());
+            }
+            collected
+        });
+
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let mut client_channel = crate::secure_transport::client_handshake(
+            crate::secure_transport::Transport::Tcp(stream), &client_identity, b"", None, 16 * 1024 * 1024,
+        ).await.unwrap();
+        send_stream(&mut client_channel, Uuid::new_v4(), &payload).await.unwrap();
+
+        let received = server_task.await.unwrap();
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn test_envelope_round_trips_request_id_and_message() {
+        let message = ClusterMessage::IndirectAck { target: "node-a".to_string() };
+        let framed = encode_envelope(42, &message).unwrap();
+
+        let (request_id, decoded) = decode_envelope(&framed).unwrap();
+        assert_eq!(request_id, 42);
+        assert!(matches!(decoded, ClusterMessage::IndirectAck { target } if target == "node-a"));
+    }
+
+    #[test]
+    fn test_decode_envelope_rejects_short_frame() {
+        assert!(decode_envelope(&[0u8, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_backoff_delay_grows_and_caps() {
+        let mut backoff = Backoff::fresh();
+        assert_eq!(backoff.delay(), MIN_RECONNECT_BACKOFF);
+
+        backoff.consecutive_failures = 1;
+        assert_eq!(backoff.delay(), MIN_RECONNECT_BACKOFF * 2);
+
+        backoff.consecutive_failures = 20;
+        assert_eq!(backoff.delay(), MAX_RECONNECT_BACKOFF);
+    }
 }
@@ -1,105 +1,557 @@
-use crate::cache::{CacheEntry, FastbuCache};
-use crate::cluster::{ClusterNode, ClusterMessage, ClusterResult, FastbuCluster, Node};
-use log::{debug, error, info, warn};
+use crate::anti_entropy::{MerkleTree, RangeEntry};
+use crate::api_cache_trait::{NodeJoinRequest, NodeStatus, PeerStatus, StatusReport};
+use crate::cache::{CacheEntry, CacheEvent, EntryVersion, EvictionPolicy, FastbuCache};
+use crate::cluster::{ClusterNode, ClusterMessage, ClusterResult, FastbuCluster, Node, SlotRange};
+use log::{debug, error, warn};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
+
+/// Total number of hash slots in the cluster, matching Redis Cluster's fixed space
+pub const SLOT_COUNT: u16 = 16384;
+
+/// CRC-16/XMODEM polynomial (x^16 + x^12 + x^5 + 1) used for slot hashing
+const CRC16_POLY: u16 = 0x1021;
+
+/// Compute CRC-16/XMODEM (init 0x0000) over a byte slice
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0x0000;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ CRC16_POLY
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Compute the hash slot for a key, honoring Redis-cluster-style hash tags:
+/// if the key contains `{...}` with a non-empty substring between the first
+/// `{` and the next `}`, only that substring is hashed so related keys
+/// (e.g. `user:{1000}:profile` and `user:{1000}:friends`) land on the same slot.
+pub fn key_slot(key: &str) -> u16 {
+    let hash_target = match key.find('{') {
+        Some(start) => match key[start + 1..].find('}') {
+            Some(len) if len > 0 => &key[start + 1..start + 1 + len],
+            _ => key,
+        },
+        None => key,
+    };
+
+    crc16(hash_target.as_bytes()) % SLOT_COUNT
+}
+
+/// Strategy used by `LoadBalancer` to pick among healthy candidate nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalancingStrategy {
+    /// Uniformly random healthy node
+    Random,
+
+    /// Advance a counter modulo the number of healthy nodes
+    #[default]
+    RoundRobin,
+
+    /// Smooth weighted round-robin, weighted by each node's configured `weight`
+    WeightedRoundRobin,
+}
+
+/// Picks a node among a set of candidates according to a configured strategy.
+/// It does no health check of its own: `ClusterCache::pick_node` hands it the
+/// healthy nodes as its last fallback, when neither the key's owner nor any of
+/// its replicas is reachable.
+pub struct LoadBalancer {
+    strategy: LoadBalancingStrategy,
+    round_robin_counter: AtomicUsize,
+    /// Smooth-weighted-round-robin running state, keyed by node ID
+    current_weights: RwLock<HashMap<String, i64>>,
+}
+
+impl LoadBalancer {
+    pub fn new(strategy: LoadBalancingStrategy) -> Self {
+        Self {
+            strategy,
+            round_robin_counter: AtomicUsize::new(0),
+            current_weights: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Select one node from `candidates`, or `None` if it's empty
+    pub async fn select(&self, candidates: &[Node]) -> Option<Node> {
+        if candidates.is_empty() {
+            return None;
+        }
+
+        match self.strategy {
+            LoadBalancingStrategy::Random => {
+                candidates.choose(&mut rand::thread_rng()).cloned()
+            }
+            LoadBalancingStrategy::RoundRobin => {
+                let idx = self.round_robin_counter.fetch_add(1, Ordering::Relaxed) % candidates.len();
+                candidates.get(idx).cloned()
+            }
+            LoadBalancingStrategy::WeightedRoundRobin => self.select_weighted(candidates).await,
+        }
+    }
+
+    /// Smooth weighted round-robin (as used by nginx/LVS): each candidate's
+    /// `current_weight` accrues by its configured `weight` every selection;
+    /// the node with the highest `current_weight` wins and has the total
+    /// weight subtracted, so high-weight nodes are picked more often without
+    /// starving low-weight ones.
+    async fn select_weighted(&self, candidates: &[Node]) -> Option<Node> {
+        let total_weight: i64 = candidates.iter().map(|n| n.weight as i64).sum();
+        if total_weight == 0 {
+            return candidates.first().cloned();
+        }
+
+        let mut current_weights = self.current_weights.write().await;
+        let mut winner: Option<(&Node, i64)> = None;
+
+        for node in candidates {
+            let current = current_weights.entry(node.id.clone()).or_insert(0);
+            *current += node.weight as i64;
+
+            if winner.is_none_or(|(_, best)| *current > best) {
+                winner = Some((node, *current));
+            }
+        }
+
+        let (winner, _) = winner?;
+        if let Some(weight) = current_weights.get_mut(&winner.id) {
+            *weight -= total_weight;
+        }
+
+        Some(winner.clone())
+    }
+}
 
 /// A cluster-aware cache that distributes data across nodes
 pub struct ClusterCache {
     /// The local cache instance
     local_cache: FastbuCache,
-    
+
     /// Reference to the cluster for node management
     cluster: Arc<RwLock<FastbuCluster>>,
+
+    /// Picks among healthy candidate nodes when the primary owner is unreachable
+    load_balancer: LoadBalancer,
+}
+
+/// Resolve the node responsible for a key: prefer slot-range ownership
+/// (Redis-cluster-style sharding) and fall back to the consistent hash ring
+/// when no node advertises a slot assignment.
+async fn resolve_owner(cluster: &FastbuCluster, key: &str) -> Option<Node> {
+    let slot = key_slot(key);
+    if let Some(node) = cluster.get_node_for_slot(slot).await {
+        return Some(node);
+    }
+    cluster.get_responsible_node(key).await
+}
+
+/// Background task: every `anti_entropy_interval`, pick a random healthy
+/// peer that actually shares ownership of part of the local keyspace and
+/// reconcile against it via Merkle-tree diff, so a node that was down (or
+/// that just dropped a `KeyUpdated` broadcast) converges with its replicas
+/// instead of staying silently divergent forever. Peers outside the local
+/// node's replica-set neighborhood are skipped entirely -- reconciling
+/// against them can't surface anything relevant and would otherwise pull
+/// the whole keyspace across shard boundaries.
+fn spawn_anti_entropy_task(local_cache: FastbuCache, cluster: Arc<RwLock<FastbuCluster>>) {
+    tokio::spawn(async move {
+        loop {
+            let interval = {
+                let cluster = cluster.read().await;
+                std::time::Duration::from_secs(cluster.get_config().cluster.anti_entropy_interval.max(1))
+            };
+            tokio::time::sleep(interval).await;
+
+            let cluster = cluster.read().await;
+            let local_id = cluster.get_local_node().id.clone();
+            let replication = cluster.get_config().cluster.replication;
+            let healthy = cluster.healthy_nodes().await;
+
+            let mut candidates = Vec::with_capacity(healthy.len());
+            for node in healthy.into_iter().filter(|node| node.id != local_id) {
+                if cluster.shares_replica_set(&local_id, &node.id, replication).await {
+                    candidates.push(node);
+                }
+            }
+
+            let Some(peer) = candidates.choose(&mut rand::thread_rng()).cloned() else {
+                continue;
+            };
+
+            if let Err(e) = reconcile_with(&local_cache, &cluster, &peer, &local_id, replication).await {
+                warn!("Anti-entropy reconciliation with {} failed: {}", peer.id, e);
+            }
+        }
+    });
+}
+
+/// Background task: drain `local_cache`'s event channel and propagate key
+/// expirations to the key's replica set, so a TTL elapsing on one node
+/// doesn't leave its replicas serving a stale copy until their own TTL
+/// catches up independently. Local evictions (`CacheEvent::Evicted`) are
+/// deliberately NOT propagated here — the evicted value is still valid and
+/// still on this node's own disk tier, so invalidating it cluster-wide
+/// would be wrong, not just unnecessary.
+fn spawn_expiry_propagation_task(cluster: Arc<RwLock<FastbuCluster>>, mut events: mpsc::Receiver<CacheEvent>) {
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            let CacheEvent::Expired { key } = event else {
+                continue;
+            };
+
+            let cluster = cluster.read().await;
+            let local_id = cluster.get_local_node().id.clone();
+            let replication = cluster.get_config().cluster.replication;
+            let replicas = cluster.get_responsible_nodes(&key, replication).await;
+
+            let message = ClusterMessage::KeyInvalidated { key: key.clone() };
+            for replica in replicas.iter().filter(|n| n.id != local_id) {
+                if let Err(e) = cluster.send_message(replica, message.clone()).await {
+                    warn!("Failed to propagate expiration of key {} to {}: {}", key, replica.id, e);
+                }
+            }
+        }
+    });
+}
+
+/// Reconcile `local_cache`'s keyspace against `peer`'s: compare Merkle
+/// roots first, and only if they disagree, descend one level at a time,
+/// querying children only of branches whose hashes actually mismatch. This
+/// bounds traffic to the divergent ranges rather than the whole keyspace.
+async fn reconcile_with(
+    local_cache: &FastbuCache,
+    cluster: &FastbuCluster,
+    peer: &Node,
+    local_id: &str,
+    replication: usize,
+) -> ClusterResult<()> {
+    let local_tree = MerkleTree::build(local_cache.snapshot_entries());
+    let root = local_tree.height();
+    let root_answer = cluster.query_merkle_node(peer, root, 0).await?;
+
+    if root_answer.hash == local_tree.root() {
+        debug!("Anti-entropy: keyspace root already matches {}", peer.id);
+        return Ok(());
+    }
+
+    let mut stack = vec![(root, 0usize)];
+    let mut ranges_reconciled = 0usize;
+
+    while let Some((level, index)) = stack.pop() {
+        let remote = if (level, index) == (root, 0) {
+            root_answer.clone()
+        } else {
+            cluster.query_merkle_node(peer, level, index).await?
+        };
+
+        let Some(local_hash) = local_tree.node_hash(level, index) else { continue };
+        if local_hash == remote.hash {
+            continue;
+        }
+
+        if level == 0 {
+            reconcile_leaf(
+                local_cache,
+                cluster,
+                peer,
+                local_id,
+                replication,
+                local_tree.range_entries(index),
+                remote.entries.unwrap_or_default(),
+            )
+            .await?;
+            ranges_reconciled += 1;
+            continue;
+        }
+
+        stack.push((level - 1, index * 2));
+        stack.push((level - 1, index * 2 + 1));
+    }
+
+    if ranges_reconciled > 0 {
+        debug!("Anti-entropy: reconciled {} divergent range(s) with {}", ranges_reconciled, peer.id);
+    }
+    Ok(())
+}
+
+/// Pull keys `peer` has that are missing or stale locally, and push keys
+/// held locally that are missing or stale on `peer`, for one leaf range
+/// whose hash didn't match. A key is only pulled or pushed if `peer`'s
+/// current replica set actually includes both the local node and `peer` --
+/// the Merkle tree is built over the whole keyspace, so without this check
+/// a divergent leaf could otherwise leak keys across shard boundaries that
+/// `peer` has no business holding (or that it's about to become stale on
+/// once ownership moves on).
+async fn reconcile_leaf(
+    local_cache: &FastbuCache,
+    cluster: &FastbuCluster,
+    peer: &Node,
+    local_id: &str,
+    replication: usize,
+    local_entries: &[RangeEntry],
+    remote_entries: Vec<RangeEntry>,
+) -> ClusterResult<()> {
+    let local_by_key: HashMap<&str, u64> = local_entries.iter().map(|e| (e.key.as_str(), e.digest)).collect();
+    let remote_by_key: HashMap<&str, u64> = remote_entries.iter().map(|e| (e.key.as_str(), e.digest)).collect();
+
+    let all_keys: Vec<&str> = local_entries
+        .iter()
+        .map(|e| e.key.as_str())
+        .chain(remote_entries.iter().map(|e| e.key.as_str()))
+        .collect();
+    let replicas_by_key = cluster.get_responsible_nodes_for_keys(&all_keys, replication).await;
+    let shared = |key: &str| {
+        replicas_by_key
+            .get(key)
+            .is_some_and(|replicas| replicas.iter().any(|n| n.id == local_id) && replicas.iter().any(|n| n.id == peer.id))
+    };
+
+    for entry in &remote_entries {
+        if local_by_key.get(entry.key.as_str()) != Some(&entry.digest) {
+            if !shared(&entry.key) {
+                continue;
+            }
+
+            match cluster.fetch_remote(peer, &entry.key).await {
+                Ok(Some(remote_entry)) => {
+                    if let Err(e) = local_cache.insert_entry(entry.key.clone(), remote_entry).await {
+                        warn!("Anti-entropy: failed to apply key {} pulled from {}: {}", entry.key, peer.id, e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Anti-entropy: failed to fetch key {} from {}: {}", entry.key, peer.id, e),
+            }
+        }
+    }
+
+    for entry in local_entries {
+        if remote_by_key.get(entry.key.as_str()) != Some(&entry.digest) {
+            if !shared(&entry.key) {
+                continue;
+            }
+
+            if let Some(cache_entry) = local_cache.get_entry(&entry.key) {
+                let message = ClusterMessage::KeyUpdated { key: entry.key.clone(), value: cache_entry };
+                if let Err(e) = cluster.send_message(peer, message).await {
+                    warn!("Anti-entropy: failed to push key {} to {}: {}", entry.key, peer.id, e);
+                }
+            }
+        }
+    }
+
+    Ok(())
 }
 
 impl ClusterCache {
     /// Create a new cluster-aware cache
-    pub fn new(cluster: FastbuCluster) -> Self {
-        // Create a new cache instance without the accessor yet
-        let instance = Self {
-            local_cache: FastbuCache::new(),
-            cluster: Arc::new(RwLock::new(cluster)),
-        };
-        
-        // We'll implement the cache accessor elsewhere since
-        // we can't capture the local_cache directly due to lifetime constraints
-        
-        instance
+    pub fn new(mut cluster: FastbuCluster) -> Self {
+        let strategy = cluster.get_config().cluster.load_balancing;
+        let (local_cache, cache_events) = FastbuCache::new_with_events(EvictionPolicy::default(), usize::MAX);
+
+        // FastbuCache is internally Arc<Mutex<..>>-backed, so these clones
+        // share storage with `local_cache` below rather than copying it.
+        let accessor_cache = local_cache.clone();
+        cluster.set_cache_accessor(move |key| accessor_cache.get_entry(key));
+
+        let writer_cache = local_cache.clone();
+        cluster.set_cache_writer(move |key, entry| {
+            let writer_cache = writer_cache.clone();
+            let key = key.to_string();
+            let entry = entry.clone();
+            tokio::spawn(async move {
+                if let Err(e) = writer_cache.insert_entry(key.clone(), entry).await {
+                    warn!("Failed to apply replicated update for key {}: {}", key, e);
+                }
+            });
+        });
+
+        let invalidator_cache = local_cache.clone();
+        cluster.set_cache_invalidator(move |key| {
+            let invalidator_cache = invalidator_cache.clone();
+            let key = key.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = invalidator_cache.remove(&key).await {
+                    warn!("Failed to apply replicated invalidation for key {}: {}", key, e);
+                }
+            });
+        });
+
+        let merkle_cache = local_cache.clone();
+        cluster.set_merkle_provider(move || MerkleTree::build(merkle_cache.snapshot_entries()));
+
+        let cluster = Arc::new(RwLock::new(cluster));
+        spawn_anti_entropy_task(local_cache.clone(), Arc::clone(&cluster));
+        spawn_expiry_propagation_task(Arc::clone(&cluster), cache_events);
+
+        Self {
+            local_cache,
+            cluster,
+            load_balancer: LoadBalancer::new(strategy),
+        }
     }
-    
+
+    /// Send a message to a peer, recording a per-peer forwarded-request
+    /// counter and latency histogram when the `metrics` feature is enabled
+    async fn send_message(
+        &self,
+        cluster: &FastbuCluster,
+        node: &Node,
+        message: ClusterMessage,
+    ) -> ClusterResult<()> {
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
+
+        let result = cluster.send_message(node, message).await;
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_forward(&node.id, start.elapsed());
+
+        result.map(|_| ())
+    }
+
+    /// Resolve which node should serve a key: the slot/ring owner if it's
+    /// reachable, otherwise the next healthy node in the key's replica set
+    /// (same ring order `get_responsible_nodes` produces), otherwise a node
+    /// picked by the configured `LoadBalancer` from the remaining healthy
+    /// nodes, falling back to the local node if nothing else is reachable.
+    async fn pick_node(&self, cluster: &FastbuCluster, key: &str) -> Node {
+        if let Some(owner) = resolve_owner(cluster, key).await {
+            if cluster.is_healthy(&owner.id).await {
+                return owner;
+            }
+            warn!("Owner {} for key {} is unhealthy, trying its replicas in ring order", owner.id, key);
+
+            let replication = cluster.get_config().cluster.replication;
+            for replica in cluster.get_responsible_nodes(key, replication).await {
+                if replica.id != owner.id && cluster.is_healthy(&replica.id).await {
+                    return replica;
+                }
+            }
+            warn!("No healthy replica for key {} either, falling back to load balancer", key);
+        }
+
+        let healthy = cluster.healthy_nodes().await;
+        match self.load_balancer.select(&healthy).await {
+            Some(node) => node,
+            None => {
+                warn!("No healthy peers available for key {}, falling back to local node", key);
+                cluster.get_local_node().clone()
+            }
+        }
+    }
+
+    /// Check `key`'s other replicas for a value the primary (`missing_from`)
+    /// reported as not found, and if one has it, push the value back to the
+    /// primary with a `KeyUpdated` so it self-heals instead of staying stale.
+    async fn read_repair(&self, cluster: &FastbuCluster, missing_from: &Node, key: &str) -> Option<String> {
+        let local_id = cluster.get_local_node().id.clone();
+        let replication = cluster.get_config().cluster.replication;
+        let replicas = cluster.get_responsible_nodes(key, replication).await;
+
+        for replica in replicas.iter().filter(|r| r.id != missing_from.id && r.id != local_id) {
+            match cluster.fetch_remote(replica, key).await {
+                Ok(Some(entry)) if entry.is_tombstone() => continue,
+                Ok(Some(entry)) => {
+                    debug!(
+                        "Read-repair: found key {} on replica {}, repairing primary {}",
+                        key, replica.id, missing_from.id
+                    );
+                    let repair = ClusterMessage::KeyUpdated { key: key.to_string(), value: entry.clone() };
+                    if let Err(e) = self.send_message(cluster, missing_from, repair).await {
+                        warn!("Failed to read-repair key {} on primary {}: {}", key, missing_from.id, e);
+                    }
+                    return Some(entry.value().to_string());
+                }
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Read-repair fetch of key {} from replica {} failed: {}", key, replica.id, e);
+                }
+            }
+        }
+
+        None
+    }
+
     /// Insert a key-value pair into the cache
     /// If this node is responsible for the key, store it locally
     /// Otherwise, forward the request to the responsible node
-    pub async fn insert(&self, key: String, value: String) -> ClusterResult<()> {
+    pub async fn insert(&self, key: String, value: String, ttl: Option<std::time::Duration>) -> ClusterResult<()> {
         debug!("Cluster insert request for key: {}", key);
 
-        // Find the node responsible for this key
+        // Find the node responsible for this key. The SWIM membership
+        // subsystem guarantees `pick_node` always resolves to a live node
+        // (the slot/ring owner, a load-balanced healthy peer, or the local
+        // node as a last resort), so there's no "no responsible node" case
+        // left to handle here.
         let cluster = self.cluster.read().await;
-        let responsible_node = cluster.get_responsible_node(&key).await;
-
-        match responsible_node {
-            Some(node) => {
-                // Get our local node information
-                let local_id = cluster.get_config().node.id.clone();
-
-                // Check if we are the responsible node
-                if node.id == local_id {
-                    debug!("This node is responsible for key: {}", key);
-                    // We are responsible for this key, store it locally
-                    match self.local_cache.insert(key.clone(), value.clone()).await {
-                        Ok(_) => {
-                            debug!("Successfully inserted key locally: {}", key);
-                            // Notify other nodes that we've updated the key (for replication)
-                            let entry = CacheEntry { value };
-                            let message = ClusterMessage::KeyUpdated { key: key.clone(), value: entry };
-                            
-                            // Get all nodes in the cluster for replication
-                            let nodes = cluster.get_nodes().await;
-                            
-                            // Send update to other nodes for redundancy (excluding ourselves)
-                            for other_node in nodes.iter().filter(|n| n.id != local_id) {
-                                debug!("Broadcasting key update to node: {}", other_node.id);
-                                if let Err(e) = cluster.send_message(other_node, message.clone()).await {
-                                    warn!("Failed to broadcast key update to node {}: {}", other_node.id, e);
-                                }
-                            }
-                            
-                            Ok(())
-                        },
-                        Err(e) => {
-                            error!("Failed to insert key locally: {}. Error: {}", key, e);
-                            Err(e.into())
-                        }
-                    }
-                } else {
-                    // Another node is responsible for this key, forward the request
-                    debug!("Forwarding insert request for key: {} to node: {}", key, node.id);
-                    // Create the message to send to the responsible node
-                    let entry = CacheEntry { value: value.clone() };
+        let node = self.pick_node(&cluster, &key).await;
+        let local_id = cluster.get_local_node().id.clone();
+
+        if node.id == local_id {
+            debug!("This node is responsible for key: {}", key);
+            // We are responsible for this key, store it locally
+            match self.local_cache.insert(key.clone(), value.clone(), ttl).await {
+                Ok(_) => {
+                    debug!("Successfully inserted key locally: {}", key);
+                    // Broadcast the entry exactly as stored locally, including
+                    // the version `insert` just stamped it with, rather than
+                    // minting a fresh (and different) one that replicas would
+                    // compare against their own copies.
+                    let entry = self.local_cache.get_entry(&key).unwrap_or_else(|| CacheEntry::new(value, ttl));
                     let message = ClusterMessage::KeyUpdated { key: key.clone(), value: entry };
-                    // Send the message to the responsible node
-                    match cluster.send_message(&node, message).await {
-                        Ok(_) => {
-                            debug!("Successfully forwarded key {} to node {}", key, node.id);
-                            return Ok(());
-                        },
-                        Err(e) => {
-                            warn!("Failed to forward key {} to node {}: {}. Storing locally as fallback.", key, node.id, e);
-                            match self.local_cache.insert(key.clone(), value).await {
-                                Ok(_) => Ok(()),
-                                Err(e) => Err(e.into()),
-                            }
+
+                    // Propagate to the key's replica set (not the whole cluster)
+                    let replication = cluster.get_config().cluster.replication;
+                    let replicas = cluster.get_responsible_nodes(&key, replication).await;
+
+                    for other_node in replicas.iter().filter(|n| n.id != local_id) {
+                        debug!("Broadcasting key update to replica: {}", other_node.id);
+                        if let Err(e) = self.send_message(&cluster, other_node, message.clone()).await {
+                            warn!("Failed to broadcast key update to node {}: {}", other_node.id, e);
                         }
                     }
+
+                    Ok(())
+                },
+                Err(e) => {
+                    error!("Failed to insert key locally: {}. Error: {}", key, e);
+                    Err(e.into())
                 }
-            },
-            None => {
-                // No responsible node found (should not happen in a properly configured cluster)
-                warn!("No responsible node found for key: {}. Storing locally.", key);
-                match self.local_cache.insert(key, value).await {
-                    Ok(_) => Ok(()),
-                    Err(e) => Err(e.into()),
+            }
+        } else {
+            // Another node is responsible for this key, forward the request.
+            // This node has no stored prior version for the key to compare
+            // against, so it stamps a fresh one off the wall clock; the
+            // owning node's own `insert_entry` still enforces last-writer-wins
+            // against whatever it already has.
+            debug!("Forwarding insert request for key: {} to node: {}", key, node.id);
+            let version = EntryVersion::next(&local_id, None);
+            let entry = CacheEntry::with_version(value.clone(), ttl, version, false);
+            let message = ClusterMessage::KeyUpdated { key: key.clone(), value: entry };
+            // Send the message to the responsible node
+            match self.send_message(&cluster, &node, message).await {
+                Ok(_) => {
+                    debug!("Successfully forwarded key {} to node {}", key, node.id);
+                    Ok(())
+                },
+                Err(e) => {
+                    // Storing locally here would silently diverge from
+                    // what the responsible node has (or doesn't have),
+                    // so a forwarding failure is reported as a real
+                    // error instead of papered over.
+                    warn!("Failed to forward key {} to node {}: {}", key, node.id, e);
+                    Err(e)
                 }
             }
         }
@@ -111,145 +563,199 @@ impl ClusterCache {
     pub async fn get(&self, key: &str) -> Option<String> {
         debug!("Cluster get request for key: {}", key);
         
-        // Find the node responsible for this key
+        // Find the node responsible for this key. `pick_node` always
+        // resolves to a live node, so there's no "no responsible node"
+        // case left to fall back on here.
         let cluster = self.cluster.read().await;
-        let responsible_node = cluster.get_responsible_node(key).await;
-        
-        match responsible_node {
-            Some(node) => {
-                // Get our local node information
-                let local_id = cluster.get_config().node.id.clone();
-                
-                // Check if we are the responsible node
-                if node.id == local_id {
-                    debug!("This node is responsible for key: {}", key);
-                    // We are responsible for this key, get it locally
+        let node = self.pick_node(&cluster, key).await;
+        let local_id = cluster.get_local_node().id.clone();
+
+        // Check if we are the responsible node
+        if node.id == local_id {
+            debug!("This node is responsible for key: {}", key);
+            // We are responsible for this key, get it locally
+            self.local_cache.get(key)
+        } else {
+            // Another node is responsible for this key
+            debug!("Fetching key: {} from responsible node: {}", key, node.id);
+
+            match cluster.fetch_remote(&node, key).await {
+                Ok(Some(entry)) if entry.is_tombstone() => {
+                    debug!("Key {} is tombstoned on node {}", key, node.id);
+                    None
+                }
+                Ok(Some(entry)) => {
+                    debug!("Successfully fetched key {} from node {}", key, node.id);
+                    Some(entry.value().to_string())
+                }
+                Ok(None) => {
+                    debug!("Key {} not found on primary node {}. Checking replicas for read-repair.", key, node.id);
+                    self.read_repair(&cluster, &node, key).await
+                }
+                Err(e) => {
+                    warn!("Failed to fetch key {} from node {}: {}. Checking local cache as fallback.", key, node.id, e);
                     self.local_cache.get(key)
-                } else {
-                    // Another node is responsible for this key
-                    debug!("Fetching key: {} from responsible node: {}", key, node.id);
-                    
-                    // Send a fetch request to the responsible node
-                    let fetch_message = ClusterMessage::FetchRequest { key: key.to_string() };
-                    
-                    match cluster.send_message(&node, fetch_message).await {
-                        Ok(_) => {
-                            debug!("Fetch request for key {} sent to node {}", key, node.id);
-                            
-                            // In a real implementation, we'd wait for a response
-                            // For now, we'll simulate the response by checking locally first,
-                            // and if not found, try a direct TCP connection to fetch the value
-                            
-                            // First check if we happen to have it locally (for faster response)
-                            if let Some(value) = self.local_cache.get(key) {
-                                debug!("Key {} found locally as fallback", key);
-                                return Some(value);
-                            }
-                            
-                            // Otherwise, try a direct fetch from the other node via TCP
-                            debug!("Attempting direct fetch from node {}", node.id);
-                            
-                            // This would be implemented with a proper protocol
-                            // For now, we'll use a simpler approach with a direct connection
-                            let fetch_result = self.direct_fetch_from_node(&node, key).await;
-                            
-                            match fetch_result {
-                                Some(value) => {
-                                    debug!("Successfully fetched key {} from node {}", key, node.id);
-                                    Some(value)
-                                }
-                                None => {
-                                    warn!("Failed to fetch key {} from node {}", key, node.id);
-                                    None
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to send fetch request to node {}: {}", node.id, e);
-                            // Fall back to local cache if communication fails
-                            self.local_cache.get(key)
-                        }
-                    }
                 }
-            },
-            None => {
-                // No responsible node found (should not happen in a properly configured cluster)
-                warn!("No responsible node found for key: {}. Checking locally.", key);
-                self.local_cache.get(key)
             }
         }
     }
     
-    /// Directly fetch a key value from another node using a TCP connection
-    async fn direct_fetch_from_node(&self, node: &Node, key: &str) -> Option<String> {
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
-        use tokio::time::timeout;
-        use std::time::Duration;
-        
-        // Connect to the node's TCP address
-        let addr = node.addr();
-        info!("Attempting to fetch key '{}' directly from node {} at {}", key, node.id, addr);
-        
-        // Add a timeout for the connection to prevent blocking indefinitely
-        let stream_result = timeout(Duration::from_secs(5), tokio::net::TcpStream::connect(addr)).await;
-        
-        let mut stream = match stream_result {
-            Ok(Ok(stream)) => stream,
-            Ok(Err(e)) => {
-                error!("Failed to connect to node {}: {}", node.id, e);
-                return None;
+    /// Remove a key from the cache
+    /// If this node is responsible for the key, remove it locally and send a
+    /// `KeyInvalidated` to the key's other replicas
+    /// Otherwise, forward a `KeyInvalidated` to the responsible node, removing
+    /// the key locally instead if that forward fails
+    pub async fn remove(&self, key: &str) -> ClusterResult<bool> {
+        debug!("Cluster remove request for key: {}", key);
+
+        // `pick_node` always resolves to a live node, so there's no "no
+        // responsible node" case left to fall back on here.
+        let cluster = self.cluster.read().await;
+        let node = self.pick_node(&cluster, key).await;
+        let local_id = cluster.get_local_node().id.clone();
+
+        if node.id == local_id {
+            debug!("This node is responsible for key: {}", key);
+            match self.local_cache.remove(key).await {
+                Ok(removed) => {
+                    let message = ClusterMessage::KeyInvalidated { key: key.to_string() };
+
+                    let replication = cluster.get_config().cluster.replication;
+                    let replicas = cluster.get_responsible_nodes(key, replication).await;
+
+                    for other_node in replicas.iter().filter(|n| n.id != local_id) {
+                        debug!("Broadcasting key invalidation to replica: {}", other_node.id);
+                        if let Err(e) = self.send_message(&cluster, other_node, message.clone()).await {
+                            warn!("Failed to broadcast key invalidation to node {}: {}", other_node.id, e);
+                        }
+                    }
+                    Ok(removed)
+                }
+                Err(e) => {
+                    error!("Failed to remove key locally: {}. Error: {}", key, e);
+                    Err(e.into())
+                }
             }
-            Err(_) => {
-                error!("Connection timeout when connecting to node {}", node.id);
-                return None;
+        } else {
+            debug!("Forwarding remove request for key: {} to node: {}", key, node.id);
+            let message = ClusterMessage::KeyInvalidated { key: key.to_string() };
+            match self.send_message(&cluster, &node, message).await {
+                Ok(_) => Ok(true),
+                Err(e) => {
+                    warn!("Failed to forward removal of key {} to node {}: {}. Removing locally as fallback.", key, node.id, e);
+                    match self.local_cache.remove(key).await {
+                        Ok(removed) => Ok(removed),
+                        Err(e) => Err(e.into()),
+                    }
+                }
             }
-        };
-        
-        // For the direct fetch approach, we'll actually implement a simpler protocol
-        // that doesn't rely on the message handling code in the cluster
+        }
+    }
 
-        // Send a simple direct fetch request: "GET:{key}"
-        let request = format!("GET:{}", key);
-        if let Err(e) = stream.write_all(request.as_bytes()).await {
-            error!("Failed to send direct request to node {}: {}", node.id, e);
-            return None;
+    /// Fetch several keys, fanning the per-key lookups out to their
+    /// responsible nodes concurrently and returning results in submission
+    /// order. Pass `sequential = true` to await each key one at a time
+    /// instead (e.g. to bound worst-case load fanned out from one request).
+    pub async fn get_many(&self, keys: &[String], sequential: bool) -> Vec<Option<String>> {
+        if sequential {
+            let mut results = Vec::with_capacity(keys.len());
+            for key in keys {
+                results.push(self.get(key).await);
+            }
+            results
+        } else {
+            futures::future::join_all(keys.iter().map(|key| self.get(key))).await
         }
-        
-        // Flush to ensure the data is sent
-        if let Err(e) = stream.flush().await {
-            error!("Failed to flush request to node {}: {}", node.id, e);
-            return None;
+    }
+
+    /// Insert several key-value pairs, fanning out concurrently and
+    /// returning each insert's result in submission order. Pass
+    /// `sequential = true` to await each insert one at a time instead.
+    pub async fn insert_many(
+        &self,
+        entries: Vec<(String, String, Option<std::time::Duration>)>,
+        sequential: bool,
+    ) -> Vec<ClusterResult<()>> {
+        if sequential {
+            let mut results = Vec::with_capacity(entries.len());
+            for (key, value, ttl) in entries {
+                results.push(self.insert(key, value, ttl).await);
+            }
+            results
+        } else {
+            futures::future::join_all(entries.into_iter().map(|(key, value, ttl)| self.insert(key, value, ttl))).await
         }
-        
-        // Read the response with a timeout
-        let mut response = String::new();
-        match timeout(Duration::from_secs(5), stream.read_to_string(&mut response)).await {
-            Ok(Ok(_)) => {
-                debug!("Received response from node {}: {}", node.id, response);
-                // Parse the response: FORMAT=FOUND:{value} or NOT_FOUND
-                if response.starts_with("FOUND:") {
-                    let value = response.strip_prefix("FOUND:").unwrap_or("").to_string();
-                    info!("Successfully fetched key '{}' from node {}", key, node.id);
-                    Some(value)
-                } else if response == "NOT_FOUND" {
-                    debug!("Key '{}' not found on node {}", key, node.id);
-                    None
-                } else {
-                    error!("Invalid response from node {}: {}", node.id, response);
-                    None
-                }
-            },
-            Ok(Err(e)) => {
-                error!("Failed to read response from node {}: {}", node.id, e);
-                None
-            },
-            Err(_) => {
-                error!("Response timeout when reading from node {}", node.id);
-                None
+    }
+
+    /// Resolve the ordered replica set (primary owner first) that `key`
+    /// would route to under the current ring, without performing a read or
+    /// write, so callers can reason about where a key lives or will land.
+    pub async fn replicas_for(&self, key: &str) -> Vec<String> {
+        let cluster = self.cluster.read().await;
+        let replication = cluster.get_config().cluster.replication;
+        cluster
+            .get_responsible_nodes(key, replication)
+            .await
+            .into_iter()
+            .map(|n| n.id)
+            .collect()
+    }
+
+    /// Report this node's identity, known peers with their reachability and
+    /// slot assignment, and aggregate key/memory counts for the local cache
+    pub async fn status(&self) -> StatusReport {
+        let cluster = self.cluster.read().await;
+        let local = cluster.get_local_node();
+        let local_id = local.id.clone();
+
+        let mut peers = Vec::new();
+        for node in cluster.get_nodes().await {
+            if node.id == local_id {
+                continue;
             }
+            peers.push(PeerStatus {
+                id: node.id.clone(),
+                host: node.host.clone(),
+                api_port: node.api_port,
+                cluster_port: node.port,
+                reachable: cluster.is_healthy(&node.id).await,
+                slots: node.slots.map(|range| (range.start, range.end)),
+            });
+        }
+
+        StatusReport {
+            node: NodeStatus {
+                id: local_id,
+                host: Some(local.host.clone()),
+                api_port: Some(local.api_port),
+                cluster_port: Some(local.port),
+            },
+            cluster_mode: true,
+            peers,
+            key_count: self.local_cache.len(),
+            approx_memory_bytes: self.local_cache.approx_memory_bytes(),
         }
     }
-    
+
+    /// Join a new peer to the running cluster without a restart
+    pub async fn add_node(&self, req: NodeJoinRequest) -> ClusterResult<()> {
+        let api_port = req.api_port.unwrap_or(req.cluster_port);
+        let mut node = Node::with_id(req.id, req.host, req.cluster_port, api_port);
+        if let Some((start, end)) = req.slots {
+            node = node.with_slots(SlotRange::new(start, end));
+        }
+
+        let cluster = self.cluster.read().await;
+        cluster.add_node(node).await
+    }
+
+    /// Remove a peer from the running cluster, redistributing its hash slots
+    /// across the remaining nodes; data held on the peer is not copied off it
+    pub async fn remove_node(&self, id: &str) -> ClusterResult<()> {
+        let cluster = self.cluster.read().await;
+        cluster.remove_node(id).await
+    }
+
     /// Get a reference to the local cache
     pub fn local_cache(&self) -> &FastbuCache {
         &self.local_cache
@@ -299,7 +805,7 @@ mod tests {
         let value = "local-test-value".to_string();
         
         // Insert should succeed
-        let result = cache.insert(key.clone(), value.clone()).await;
+        let result = cache.insert(key.clone(), value.clone(), None).await;
         assert!(result.is_ok(), "Local insert should succeed");
         
         // Get should return the inserted value
@@ -307,4 +813,63 @@ mod tests {
         assert!(retrieved.is_some(), "Local get should find the key");
         assert_eq!(retrieved.unwrap(), value, "Retrieved value should match inserted value");
     }
+
+    #[test]
+    fn test_key_slot_in_range() {
+        let slot = key_slot("some-key");
+        assert!(slot < SLOT_COUNT);
+    }
+
+    #[test]
+    fn test_key_slot_deterministic() {
+        assert_eq!(key_slot("foo"), key_slot("foo"));
+    }
+
+    #[test]
+    fn test_hash_tag_co_locates_keys() {
+        // Keys sharing a hash tag must land on the same slot, even though the
+        // full keys differ.
+        assert_eq!(key_slot("user:{1000}:profile"), key_slot("other:{1000}:friends"));
+    }
+
+    #[test]
+    fn test_empty_hash_tag_is_ignored() {
+        // An empty `{}` should not be treated as a hash tag - the whole key is hashed.
+        assert_ne!(key_slot("{}foo"), key_slot("{}bar"));
+    }
+
+    fn node(id: &str, weight: u32) -> Node {
+        let mut node = Node::with_id(id.to_string(), "127.0.0.1".to_string(), 7000, 3000);
+        node.weight = weight;
+        node
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_cycles_through_candidates() {
+        let balancer = LoadBalancer::new(LoadBalancingStrategy::RoundRobin);
+        let candidates = vec![node("a", 1), node("b", 1), node("c", 1)];
+
+        let picks: Vec<String> = collect_picks(&balancer, &candidates, 4).await;
+        assert_eq!(picks, vec!["a", "b", "c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_round_robin_favors_heavier_node() {
+        let balancer = LoadBalancer::new(LoadBalancingStrategy::WeightedRoundRobin);
+        let candidates = vec![node("heavy", 3), node("light", 1)];
+
+        let picks: Vec<String> = collect_picks(&balancer, &candidates, 4).await;
+        let heavy_picks = picks.iter().filter(|id| *id == "heavy").count();
+        assert!(heavy_picks >= 3, "heavier node should be picked most of the time: {:?}", picks);
+    }
+
+    /// Small helper to repeatedly call `select` and collect the chosen node IDs
+    async fn collect_picks(balancer: &LoadBalancer, candidates: &[Node], n: usize) -> Vec<String> {
+        let mut picks = Vec::with_capacity(n);
+        for _ in 0..n {
+            let chosen = balancer.select(candidates).await.expect("candidates is non-empty");
+            picks.push(chosen.id);
+        }
+        picks
+    }
 }
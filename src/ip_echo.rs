@@ -0,0 +1,167 @@
+//! A lightweight TCP echo service (modeled on Solana's ip-echo server) that
+//! lets a node discover its own publicly-reachable address. A node behind
+//! NAT or in a container usually has a `host` in its config that's right
+//! for binding but wrong for advertising to peers, so instead of trusting
+//! that value it asks a seed: connect, and whatever source address the
+//! seed sees on the accepted socket is this node's real public address.
+//!
+//! The request can also carry a list of ports the connecting node is about
+//! to advertise as listening on; the server dials each of them back and
+//! reports which ones it could actually reach, so a node can warn loudly if
+//! its advertised address turns out to be unroutable rather than silently
+//! gossiping something peers can never connect to.
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Sent by a node to an ip-echo server on connect
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IpEchoRequest {
+    /// Ports the connecting node is about to advertise as listening on; the
+    /// server will try to dial each one back and report which are reachable
+    pub reachability_check_ports: Vec<u16>,
+}
+
+/// The server's reply: the address it observed the connection come from,
+/// plus which of the requested ports it could successfully connect back to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpEchoResponse {
+    pub address: IpAddr,
+    pub reachable_ports: Vec<u16>,
+}
+
+const REACHABILITY_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Cap on a frame's declared length, applied before allocating a buffer for
+/// it. This server is meant to be reachable by arbitrary/NAT'd peers, so an
+/// unchecked length prefix would let a single connection trigger a
+/// multi-gigabyte allocation before a single payload byte is read. The
+/// request/response bodies here are tiny (a port list, an address), so this
+/// is generous headroom rather than a tight fit.
+const MAX_FRAME_LEN: u32 = 1024 * 1024;
+
+async fn read_frame(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let len = stream.read_u32().await?;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame length {} exceeds maximum of {}", len, MAX_FRAME_LEN),
+        ));
+    }
+    let mut buf = vec![0u8; len as usize];
+    stream.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+async fn write_frame(stream: &mut TcpStream, data: &[u8]) -> io::Result<()> {
+    stream.write_u32(data.len() as u32).await?;
+    stream.write_all(data).await?;
+    stream.flush().await
+}
+
+/// Start the ip-echo server, bound to `bind_addr`. Runs until the process
+/// exits; errors handling one connection don't affect any other.
+pub async fn start_ip_echo_server(bind_addr: SocketAddr) -> io::Result<()> {
+    let listener = TcpListener::bind(bind_addr).await?;
+    log::info!("Started ip-echo server on {}", bind_addr);
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer_addr)) => {
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_echo_connection(stream, peer_addr).await {
+                            debug!("ip-echo connection from {} failed: {}", peer_addr, e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    warn!("ip-echo server failed to accept connection: {}", e);
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn handle_echo_connection(mut stream: TcpStream, peer_addr: SocketAddr) -> io::Result<()> {
+    let request_bytes = read_frame(&mut stream).await?;
+    let request: IpEchoRequest = bincode::deserialize(&request_bytes)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut reachable_ports = Vec::new();
+    for port in &request.reachability_check_ports {
+        let probe_addr = SocketAddr::new(peer_addr.ip(), *port);
+        match tokio::time::timeout(REACHABILITY_CHECK_TIMEOUT, TcpStream::connect(probe_addr)).await {
+            Ok(Ok(_)) => reachable_ports.push(*port),
+            Ok(Err(e)) => debug!("Reachability check of {} failed: {}", probe_addr, e),
+            Err(_) => debug!("Reachability check of {} timed out", probe_addr),
+        }
+    }
+
+    let response = IpEchoResponse {
+        address: peer_addr.ip(),
+        reachable_ports,
+    };
+    let response_bytes = bincode::serialize(&response)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    write_frame(&mut stream, &response_bytes).await
+}
+
+/// Connect to an ip-echo server at `echo_addr` (e.g. a seed's
+/// `host:ip_echo_port`) and ask it what address this connection appears to
+/// come from, along with which of `reachability_check_ports` it could dial
+/// back successfully.
+pub async fn query_ip_echo(echo_addr: &str, reachability_check_ports: Vec<u16>) -> io::Result<IpEchoResponse> {
+    let mut stream = TcpStream::connect(echo_addr).await?;
+
+    let request = IpEchoRequest { reachability_check_ports };
+    let request_bytes = bincode::serialize(&request)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    write_frame(&mut stream, &request_bytes).await?;
+
+    let response_bytes = read_frame(&mut stream).await?;
+    bincode::deserialize(&response_bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_echo_reports_observed_address() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let bind_addr = listener.local_addr().unwrap();
+        drop(listener);
+        start_ip_echo_server(bind_addr).await.unwrap();
+
+        let response = query_ip_echo(&bind_addr.to_string(), vec![]).await.unwrap();
+        assert_eq!(response.address, "127.0.0.1".parse::<IpAddr>().unwrap());
+        assert!(response.reachable_ports.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_echo_reachability_check() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let bind_addr = listener.local_addr().unwrap();
+        drop(listener);
+        start_ip_echo_server(bind_addr).await.unwrap();
+
+        // A listener the echo server should be able to dial back...
+        let open_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open_port = open_listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let _ = open_listener.accept().await;
+        });
+
+        // ...and a closed port it shouldn't (nothing bound to port 1).
+        let response = query_ip_echo(&bind_addr.to_string(), vec![open_port, 1]).await.unwrap();
+        assert_eq!(response.reachable_ports, vec![open_port]);
+    }
+}
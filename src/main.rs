@@ -1,9 +1,16 @@
+mod anti_entropy; // Merkle-tree reconciliation of replica keyspaces
 mod api;
 mod api_cache; // Add the new api_cache module
 mod api_cache_trait; // Add the trait for API caches
 mod cache;
 mod cluster; // Add the new cluster module
 mod cluster_cache; // Add the new cluster_cache module
+mod ip_echo; // Public address self-discovery for NAT'd nodes
+#[cfg(feature = "metrics")]
+mod metrics; // Prometheus metrics, behind the `metrics` feature
+mod redis_cache; // Redis-backed shared ApiCache implementation
+mod resp; // RESP (Redis protocol) frontend
+mod secure_transport; // Authenticated, encrypted node-to-node transport
 mod storage;
 
 use crate::cache::FastbuCache;
@@ -11,27 +18,65 @@ use crate::cluster::{ClusterConfig, ClusterNode, FastbuCluster, load_cluster_con
 use crate::cluster_cache::ClusterCache; // Import cluster cache
 use crate::api_cache::ClusterAwareApiCache; // Import API cache wrapper
 use crate::api_cache_trait::ApiCache; // Import API cache trait
-use clap::Parser;
+use crate::redis_cache::RedisCache; // Shared Redis-backed cache
+use crate::storage::RocksDbStorage; // RocksDB-backed disk tier
+use clap::{Parser, ValueEnum};
 use env_logger::Builder;
 use log::{info, warn, LevelFilter};
+use serde::Deserialize;
 use std::error::Error;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 const DEFAULT_HOST: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 3031;
+const DEFAULT_RESP_PORT: u16 = 6380;
+const DEFAULT_CONFIG_PATH: &str = "fastbu.toml";
+const DEFAULT_REDIS_URL: &str = "redis://127.0.0.1:6379/";
+const DEFAULT_ROCKSDB_PATH: &str = "cache_storage/rocksdb";
+
+/// Which wire protocol(s) the server should expose
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Protocol {
+    Http,
+    Resp,
+    Both,
+}
+
+/// Which storage backend serves cache reads/writes in standalone mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Backend {
+    /// Local in-memory tier backed by per-instance disk storage (default)
+    Disk,
+    /// Shared state via a Redis instance, for a consistent view across instances
+    Redis,
+}
+
+/// Which disk tier `FastbuCache` persists through when `--backend disk` is
+/// selected; irrelevant when `--backend redis` is used instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StorageEngine {
+    /// Append-only, one-file-per-key store (default)
+    File,
+    /// Embedded RocksDB instance, for crash-consistent persistence without
+    /// the append-only growth of the file engine
+    Rocksdb,
+}
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Host to bind to
-    #[arg(short = 'H', long, default_value = "127.0.0.1")]
-    host: String,
+    /// Host to bind to (overrides config file)
+    #[arg(short = 'H', long)]
+    host: Option<String>,
+
+    /// Port to listen on (overrides config file)
+    #[arg(short, long)]
+    port: Option<u16>,
 
-    /// Port to listen on
-    #[arg(short, long, default_value_t = 3031)]
-    port: u16,
-    
     /// Run in cluster mode
     #[arg(long)]
     cluster: bool,
@@ -43,6 +88,61 @@ struct Args {
     /// Node ID (overrides config file)
     #[arg(long)]
     node_id: Option<String>,
+
+    /// Load-balancing strategy for forwarding cluster requests (overrides config file)
+    #[arg(long, value_enum)]
+    load_balancing: Option<crate::cluster_cache::LoadBalancingStrategy>,
+
+    /// Eviction policy for the bounded in-memory cache tier (overrides config file).
+    /// Used by the standalone disk backend only; cluster mode always uses the
+    /// default policy.
+    #[arg(long, value_enum)]
+    eviction_policy: Option<crate::cache::EvictionPolicy>,
+
+    /// Maximum approximate bytes the in-memory cache tier may hold before
+    /// evicting (overrides config file); unset means effectively unbounded.
+    /// Used by the standalone disk backend only; the cluster-mode cache tier
+    /// is always unbounded.
+    #[arg(long)]
+    cache_capacity: Option<usize>,
+
+    /// Wire protocol(s) to serve: http, resp, or both (overrides config file)
+    #[arg(long, value_enum)]
+    protocol: Option<Protocol>,
+
+    /// Port for the RESP (Redis protocol) listener, when enabled (overrides config file)
+    #[arg(long)]
+    resp_port: Option<u16>,
+
+    /// Storage backend for standalone mode: disk (default) or redis (overrides config file)
+    #[arg(long, value_enum)]
+    backend: Option<Backend>,
+
+    /// Redis connection URL, used when `--backend redis` is selected (overrides config file)
+    #[arg(long)]
+    redis_url: Option<String>,
+
+    /// Disk tier for `--backend disk`: file (default) or rocksdb (overrides config file)
+    #[arg(long, value_enum)]
+    storage_engine: Option<StorageEngine>,
+
+    /// RocksDB data directory, used when `--storage-engine rocksdb` is selected (overrides config file)
+    #[arg(long)]
+    rocksdb_path: Option<PathBuf>,
+
+    /// Bearer token accepted by the HTTP API; repeatable to allow several
+    /// tokens (overrides config file). An empty set disables auth entirely.
+    #[arg(long = "auth-token")]
+    auth_token: Vec<String>,
+
+    /// Also require a bearer token on GET /get/{key}; by default reads are
+    /// public and only writes are gated (overrides config file)
+    #[arg(long)]
+    require_auth_get: bool,
+
+    /// Path to the standalone-mode config file
+    #[arg(long, default_value = DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
 }
 
 #[derive(Debug)]
@@ -52,6 +152,18 @@ struct Config {
     cluster_mode: bool,
     cluster_config_path: PathBuf,
     node_id: Option<String>,
+    load_balancing: Option<crate::cluster_cache::LoadBalancingStrategy>,
+    protocol: Protocol,
+    resp_port: u16,
+    log_level: Option<String>,
+    cache_capacity: Option<usize>,
+    eviction_policy: crate::cache::EvictionPolicy,
+    backend: Backend,
+    redis_url: String,
+    storage_engine: StorageEngine,
+    rocksdb_path: PathBuf,
+    auth_tokens: Vec<String>,
+    require_auth_get: bool,
 }
 
 impl Default for Config {
@@ -62,39 +174,238 @@ impl Default for Config {
             cluster_mode: false,
             cluster_config_path: PathBuf::from("cluster.toml"),
             node_id: None,
+            load_balancing: None,
+            protocol: Protocol::Http,
+            resp_port: DEFAULT_RESP_PORT,
+            log_level: None,
+            cache_capacity: None,
+            eviction_policy: crate::cache::EvictionPolicy::default(),
+            backend: Backend::Disk,
+            redis_url: DEFAULT_REDIS_URL.to_string(),
+            storage_engine: StorageEngine::File,
+            rocksdb_path: PathBuf::from(DEFAULT_ROCKSDB_PATH),
+            auth_tokens: Vec::new(),
+            require_auth_get: false,
         }
     }
 }
 
-fn setup_logging() {
+impl Config {
+    /// Resolve the effective configuration from, in order of precedence,
+    /// CLI args, environment variables, the standalone config file (`--config`,
+    /// default `fastbu.toml`), then built-in defaults.
+    fn load(args: &Args) -> Self {
+        let file = load_file_config(&args.config);
+
+        let host = args
+            .host
+            .clone()
+            .or_else(|| env_string("FASTBU_HOST"))
+            .or(file.host)
+            .unwrap_or_else(|| DEFAULT_HOST.to_string());
+
+        let port = args
+            .port
+            .or_else(|| env_parse("FASTBU_PORT"))
+            .or(file.port)
+            .unwrap_or(DEFAULT_PORT);
+
+        let protocol = args
+            .protocol
+            .or_else(|| env_protocol("FASTBU_PROTOCOL"))
+            .or(file.protocol)
+            .unwrap_or(Protocol::Http);
+
+        let resp_port = args
+            .resp_port
+            .or_else(|| env_parse("FASTBU_RESP_PORT"))
+            .or(file.resp_port)
+            .unwrap_or(DEFAULT_RESP_PORT);
+
+        let log_level = env_string("FASTBU_LOG_LEVEL").or(file.log_level);
+        let cache_capacity = args
+            .cache_capacity
+            .or_else(|| env_parse("FASTBU_CACHE_CAPACITY"))
+            .or(file.cache_capacity);
+
+        let eviction_policy = args
+            .eviction_policy
+            .or_else(|| env_eviction_policy("FASTBU_EVICTION_POLICY"))
+            .or(file.eviction_policy)
+            .unwrap_or_default();
+
+        let backend = args
+            .backend
+            .or_else(|| env_backend("FASTBU_BACKEND"))
+            .or(file.backend)
+            .unwrap_or(Backend::Disk);
+
+        let redis_url = args
+            .redis_url
+            .clone()
+            .or_else(|| env_string("FASTBU_REDIS_URL"))
+            .or(file.redis_url)
+            .unwrap_or_else(|| DEFAULT_REDIS_URL.to_string());
+
+        let storage_engine = args
+            .storage_engine
+            .or_else(|| env_storage_engine("FASTBU_STORAGE_ENGINE"))
+            .or(file.storage_engine)
+            .unwrap_or(StorageEngine::File);
+
+        let rocksdb_path = args
+            .rocksdb_path
+            .clone()
+            .or_else(|| env_string("FASTBU_ROCKSDB_PATH").map(PathBuf::from))
+            .or(file.rocksdb_path)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_ROCKSDB_PATH));
+
+        let auth_tokens = if !args.auth_token.is_empty() {
+            args.auth_token.clone()
+        } else {
+            env_string_list("FASTBU_AUTH_TOKENS")
+                .or(file.auth_tokens)
+                .unwrap_or_default()
+        };
+
+        let require_auth_get = args.require_auth_get
+            || env_parse("FASTBU_REQUIRE_AUTH_GET")
+                .or(file.require_auth_get)
+                .unwrap_or(false);
+
+        Config {
+            host,
+            port,
+            cluster_mode: args.cluster,
+            cluster_config_path: args.cluster_config.clone(),
+            node_id: args.node_id.clone(),
+            load_balancing: args.load_balancing,
+            protocol,
+            resp_port,
+            log_level,
+            cache_capacity,
+            eviction_policy,
+            backend,
+            redis_url,
+            storage_engine,
+            rocksdb_path,
+            auth_tokens,
+            require_auth_get,
+        }
+    }
+}
+
+/// Settings readable from the standalone-mode `fastbu.toml` config file.
+/// Every field is optional so the file can set as little or as much as needed,
+/// with CLI args and environment variables taking precedence over it.
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    host: Option<String>,
+    port: Option<u16>,
+    protocol: Option<Protocol>,
+    resp_port: Option<u16>,
+    log_level: Option<String>,
+    cache_capacity: Option<usize>,
+    eviction_policy: Option<crate::cache::EvictionPolicy>,
+    backend: Option<Backend>,
+    redis_url: Option<String>,
+    storage_engine: Option<StorageEngine>,
+    rocksdb_path: Option<PathBuf>,
+    auth_tokens: Option<Vec<String>>,
+    require_auth_get: Option<bool>,
+}
+
+fn load_file_config(path: &PathBuf) -> FileConfig {
+    match std::fs::read_to_string(path) {
+        Ok(content) => match toml::from_str(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Failed to parse config file {:?}: {}", path, e);
+                FileConfig::default()
+            }
+        },
+        Err(_) => FileConfig::default(),
+    }
+}
+
+fn env_string(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    env_string(name).and_then(|v| v.parse().ok())
+}
+
+/// Comma-separated list, e.g. `FASTBU_AUTH_TOKENS=tok-a,tok-b`.
+fn env_string_list(name: &str) -> Option<Vec<String>> {
+    env_string(name).map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+}
+
+fn env_protocol(name: &str) -> Option<Protocol> {
+    env_string(name).and_then(|v| match v.to_lowercase().as_str() {
+        "http" => Some(Protocol::Http),
+        "resp" => Some(Protocol::Resp),
+        "both" => Some(Protocol::Both),
+        _ => None,
+    })
+}
+
+fn env_eviction_policy(name: &str) -> Option<crate::cache::EvictionPolicy> {
+    env_string(name).and_then(|v| match v.to_lowercase().as_str() {
+        "lru" => Some(crate::cache::EvictionPolicy::Lru),
+        "lfu" => Some(crate::cache::EvictionPolicy::Lfu),
+        _ => None,
+    })
+}
+
+fn env_backend(name: &str) -> Option<Backend> {
+    env_string(name).and_then(|v| match v.to_lowercase().as_str() {
+        "disk" => Some(Backend::Disk),
+        "redis" => Some(Backend::Redis),
+        _ => None,
+    })
+}
+
+fn env_storage_engine(name: &str) -> Option<StorageEngine> {
+    env_string(name).and_then(|v| match v.to_lowercase().as_str() {
+        "file" => Some(StorageEngine::File),
+        "rocksdb" => Some(StorageEngine::Rocksdb),
+        _ => None,
+    })
+}
+
+fn setup_logging(file_log_level: Option<&str>) {
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .or_else(|| file_log_level.map(|s| s.to_string()))
+        .unwrap_or_else(|| "debug".to_string());
+
     Builder::new()
         .filter_level(LevelFilter::Debug) // Change from Info to Debug
-        .parse_filters(&std::env::var("RUST_LOG").unwrap_or_else(|_| "debug".to_string())) // Respect RUST_LOG
+        .parse_filters(&level)
         .format_timestamp(None)
         .init();
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    // Setup logging
-    setup_logging();
-    info!("Starting Fastbu cache server...");
-
     // Parse command-line arguments
     let args = Args::parse();
-    
-    let config = Config {
-        host: args.host.clone(),
-        port: args.port,
-        cluster_mode: args.cluster,
-        cluster_config_path: args.cluster_config.clone(),
-        node_id: args.node_id.clone(),
-    };
+
+    // Resolve settings from CLI args > env vars > config file > defaults
+    let config = Config::load(&args);
+
+    // Setup logging (RUST_LOG env var > config file log_level > debug default)
+    setup_logging(config.log_level.as_deref());
+    info!("Starting Fastbu cache server...");
 
     info!("Server configuration:");
     info!("Host: {}", config.host);
     info!("Port: {}", config.port);
-    
+    if let Some(capacity) = config.cache_capacity {
+        info!("Cache capacity: {} bytes ({:?} eviction)", capacity, config.eviction_policy);
+    }
+
     if config.cluster_mode {
         info!("Running in cluster mode");
         info!("Cluster config path: {:?}", config.cluster_config_path);
@@ -118,7 +429,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
             info!("Overriding node ID from command-line: {}", node_id);
             cluster_config.node.id = node_id.clone();
         }
-        
+
+        if let Some(load_balancing) = config.load_balancing {
+            info!("Overriding load-balancing strategy from command-line: {:?}", load_balancing);
+            cluster_config.cluster.load_balancing = load_balancing;
+        }
+
+
         // If command-line options were provided, they override the config
         // But we always honor the internal port (node.port) from the config file
         // This ensures each node can have its own unique cluster communication port
@@ -157,9 +474,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
         // Create a new API handler that wraps the cluster cache
         // Here we'll need to adapt the API module to work with the cluster cache
         let cluster_cache_arc = Arc::new(cluster_cache);
-        let api_cache = ClusterAwareApiCache::new(Arc::clone(&cluster_cache_arc));
-        
-        crate::api::start_server(api_cache, config.host, config.port).await?;
+        let api_cache = Arc::new(ClusterAwareApiCache::new(Arc::clone(&cluster_cache_arc)));
+
+        serve_protocols(api_cache, &config).await?;
     } else {
         // Run in standalone mode
         run_standalone_mode(config).await?;
@@ -170,15 +487,73 @@ async fn main() -> Result<(), Box<dyn Error>> {
 }
 
 async fn run_standalone_mode(config: Config) -> Result<(), Box<dyn Error>> {
-    // Initialize the cache
-    let cache = FastbuCache::new();
-    info!("Cache initialized successfully");
-
-    // Start the server
     info!("Starting server on {}:{}", config.host, config.port);
 
-    // Use the ? operator to propagate errors
-    crate::api::start_server(cache, config.host, config.port).await?;
-    
+    match config.backend {
+        Backend::Disk => {
+            let max_bytes = config.cache_capacity.unwrap_or(usize::MAX);
+            let cache = match config.storage_engine {
+                StorageEngine::File => Arc::new(FastbuCache::new(config.eviction_policy, max_bytes)),
+                StorageEngine::Rocksdb => {
+                    info!("Opening rocksdb storage engine at {:?}", config.rocksdb_path);
+                    let storage = RocksDbStorage::open(&config.rocksdb_path)?;
+                    Arc::new(FastbuCache::with_storage(
+                        config.eviction_policy,
+                        max_bytes,
+                        Box::new(storage),
+                    ))
+                }
+            };
+            info!("Cache initialized successfully (disk backend, {:?} engine)", config.storage_engine);
+            serve_protocols(cache, &config).await
+        }
+        Backend::Redis => {
+            info!("Connecting to redis backend at {}", config.redis_url);
+            let cache = Arc::new(RedisCache::connect(&config.redis_url).await?);
+            info!("Cache initialized successfully (redis backend)");
+            serve_protocols(cache, &config).await
+        }
+    }
+}
+
+/// Start whichever wire-protocol listener(s) were requested, all backed by the
+/// same cache instance behind a single `Arc`.
+async fn serve_protocols<T: ApiCache + 'static>(
+    cache: Arc<T>,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let auth = crate::api::AuthConfig::new(config.auth_tokens.clone(), config.require_auth_get);
+
+    match config.protocol {
+        Protocol::Http => {
+            crate::api::start_server(cache, config.host.clone(), config.port, auth).await?;
+        }
+        Protocol::Resp => {
+            crate::resp::start_server(cache, config.host.clone(), config.resp_port).await?;
+        }
+        Protocol::Both => {
+            info!(
+                "Serving HTTP on {}:{} and RESP on {}:{}",
+                config.host, config.port, config.host, config.resp_port
+            );
+            let http_cache = Arc::clone(&cache);
+            let http_host = config.host.clone();
+            let http_port = config.port;
+            let resp_host = config.host.clone();
+            let resp_port = config.resp_port;
+
+            let http_task = tokio::spawn(async move {
+                crate::api::start_server(http_cache, http_host, http_port, auth).await
+            });
+            let resp_task = tokio::spawn(async move {
+                crate::resp::start_server(cache, resp_host, resp_port).await
+            });
+
+            let (http_result, resp_result) = tokio::join!(http_task, resp_task);
+            http_result??;
+            resp_result??;
+        }
+    }
+
     Ok(())
 }
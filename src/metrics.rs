@@ -0,0 +1,111 @@
+//! Prometheus metrics for cache operations, gated entirely behind the
+//! `metrics` cargo feature so the default build carries no extra dependency
+//! weight. Both `FastbuCache` and `ClusterAwareApiCache` report through the
+//! same metric names so standalone and cluster deployments are comparable.
+
+use once_cell::sync::Lazy;
+use prometheus::{HistogramVec, IntCounterVec, IntGauge, Registry, TextEncoder};
+
+static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+static REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        prometheus::Opts::new("fastbu_requests_total", "Total cache operations by type"),
+        &["operation"],
+    )
+    .expect("valid metric");
+    REGISTRY
+        .register(Box::new(counter.clone()))
+        .expect("metric not already registered");
+    counter
+});
+
+static CACHE_LOOKUPS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        prometheus::Opts::new("fastbu_cache_lookups_total", "Cache get() outcomes"),
+        &["outcome"], // "hit" | "miss"
+    )
+    .expect("valid metric");
+    REGISTRY
+        .register(Box::new(counter.clone()))
+        .expect("metric not already registered");
+    counter
+});
+
+static KEY_COUNT: Lazy<IntGauge> = Lazy::new(|| {
+    let gauge = IntGauge::new(
+        "fastbu_key_count",
+        "Current number of keys held in the local cache",
+    )
+    .expect("valid metric");
+    REGISTRY
+        .register(Box::new(gauge.clone()))
+        .expect("metric not already registered");
+    gauge
+});
+
+static FORWARDED_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        prometheus::Opts::new(
+            "fastbu_forwarded_requests_total",
+            "Requests forwarded from this node to each peer",
+        ),
+        &["peer"],
+    )
+    .expect("valid metric");
+    REGISTRY
+        .register(Box::new(counter.clone()))
+        .expect("metric not already registered");
+    counter
+});
+
+static FORWARD_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    let histogram = HistogramVec::new(
+        prometheus::HistogramOpts::new(
+            "fastbu_forward_latency_seconds",
+            "Latency of requests forwarded to a peer",
+        ),
+        &["peer"],
+    )
+    .expect("valid metric");
+    REGISTRY
+        .register(Box::new(histogram.clone()))
+        .expect("metric not already registered");
+    histogram
+});
+
+/// Record that an operation (`get`, `set`, `del`) was served
+pub fn record_request(operation: &str) {
+    REQUESTS_TOTAL.with_label_values(&[operation]).inc();
+}
+
+/// Record whether a `get` found the key
+pub fn record_lookup(hit: bool) {
+    let outcome = if hit { "hit" } else { "miss" };
+    CACHE_LOOKUPS_TOTAL.with_label_values(&[outcome]).inc();
+}
+
+/// Update the current key-count gauge
+pub fn set_key_count(count: usize) {
+    KEY_COUNT.set(count as i64);
+}
+
+/// Record a request forwarded to a peer node and how long it took
+pub fn record_forward(peer_id: &str, duration: std::time::Duration) {
+    FORWARDED_REQUESTS_TOTAL
+        .with_label_values(&[peer_id])
+        .inc();
+    FORWARD_LATENCY_SECONDS
+        .with_label_values(&[peer_id])
+        .observe(duration.as_secs_f64());
+}
+
+/// Render all registered metrics in Prometheus text exposition format
+pub fn gather() -> String {
+    let metric_families = REGISTRY.gather();
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&metric_families, &mut buffer)
+        .expect("metrics encode to valid UTF-8 text");
+    String::from_utf8(buffer).unwrap_or_default()
+}
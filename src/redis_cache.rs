@@ -0,0 +1,151 @@
+use crate::api_cache_trait::{ApiCache, NodeStatus, StatusReport};
+use crate::cache::CacheEntry;
+use async_trait::async_trait;
+use log::{debug, error};
+use redis::AsyncCommands;
+use std::io;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// `ApiCache` backed by a shared Redis instance instead of the local disk tier,
+/// so multiple Fastbu processes can serve a consistent view of the same data.
+/// Entries are stored as bincode-encoded `CacheEntry`s; TTLs are enforced by
+/// Redis itself (`SET ... EX`) rather than re-checked on read.
+pub struct RedisCache {
+    conn: redis::aio::ConnectionManager,
+    instance_id: String,
+}
+
+impl RedisCache {
+    /// Connect to `redis_url` (e.g. `redis://127.0.0.1:6379/`).
+    pub async fn connect(redis_url: &str) -> Result<Self, io::Error> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| io::Error::other(format!("invalid redis url {}: {}", redis_url, e)))?;
+        let conn = client
+            .get_connection_manager()
+            .await
+            .map_err(|e| io::Error::other(format!("failed to connect to redis at {}: {}", redis_url, e)))?;
+
+        Ok(RedisCache {
+            conn,
+            instance_id: Uuid::new_v4().to_string(),
+        })
+    }
+
+    fn encode(entry: &CacheEntry) -> Result<Vec<u8>, io::Error> {
+        bincode::serialize(entry).map_err(|e| io::Error::other(format!("failed to encode entry: {}", e)))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<CacheEntry, io::Error> {
+        bincode::deserialize(bytes).map_err(|e| io::Error::other(format!("failed to decode entry: {}", e)))
+    }
+}
+
+#[async_trait]
+impl ApiCache for RedisCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        let mut conn = self.conn.clone();
+        let bytes: Option<Vec<u8>> = match conn.get(key).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!("Redis GET failed for key: {}. Error: {}", key, e);
+                return None;
+            }
+        };
+
+        match bytes {
+            Some(bytes) => match Self::decode(&bytes) {
+                Ok(entry) => Some(entry.value().to_string()),
+                Err(e) => {
+                    error!("Failed to decode redis entry for key: {}. Error: {}", key, e);
+                    None
+                }
+            },
+            None => {
+                debug!("Key not found in redis: {}", key);
+                None
+            }
+        }
+    }
+
+    async fn set(&self, key: String, value: String) -> Result<(), io::Error> {
+        self.set_with_ttl(key, value, None).await
+    }
+
+    async fn set_with_ttl(&self, key: String, value: String, ttl: Option<Duration>) -> Result<(), io::Error> {
+        let entry = CacheEntry::new(value, ttl);
+        let bytes = Self::encode(&entry)?;
+
+        let mut conn = self.conn.clone();
+        let result = match ttl {
+            Some(ttl) => conn.set_ex(&key, bytes, ttl.as_secs().max(1)).await,
+            None => conn.set(&key, bytes).await,
+        };
+
+        result.map_err(|e| {
+            error!("Redis SET failed for key: {}. Error: {}", key, e);
+            io::Error::other(format!("redis set failed: {}", e))
+        })
+    }
+
+    async fn del(&self, key: &str) -> Result<bool, io::Error> {
+        let mut conn = self.conn.clone();
+        let removed: i64 = conn.del(key).await.map_err(|e| {
+            error!("Redis DEL failed for key: {}. Error: {}", key, e);
+            io::Error::other(format!("redis del failed: {}", e))
+        })?;
+        Ok(removed > 0)
+    }
+
+    async fn status(&self) -> StatusReport {
+        let mut conn = self.conn.clone();
+        let key_count: usize = redis::cmd("DBSIZE")
+            .query_async(&mut conn)
+            .await
+            .unwrap_or_else(|e| {
+                error!("Redis DBSIZE failed: {}", e);
+                0
+            });
+
+        StatusReport {
+            node: NodeStatus {
+                id: self.instance_id.clone(),
+                host: None,
+                api_port: None,
+                cluster_port: None,
+            },
+            cluster_mode: false,
+            peers: Vec::new(),
+            key_count,
+            // Not tracked: memory usage is Redis's to report (e.g. via `INFO
+            // memory`), not something this process measures itself.
+            approx_memory_bytes: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let entry = CacheEntry::new("value".to_string(), Some(Duration::from_secs(30)));
+        let bytes = RedisCache::encode(&entry).unwrap();
+        let decoded = RedisCache::decode(&bytes).unwrap();
+        assert_eq!(decoded.value(), entry.value());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_without_ttl() {
+        let entry = CacheEntry::new("no-ttl".to_string(), None);
+        let bytes = RedisCache::encode(&entry).unwrap();
+        let decoded = RedisCache::decode(&bytes).unwrap();
+        assert_eq!(decoded.value(), "no-ttl");
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage_bytes() {
+        assert!(RedisCache::decode(b"not a valid bincode payload").is_err());
+    }
+}
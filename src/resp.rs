@@ -0,0 +1,344 @@
+use crate::api_cache_trait::ApiCache;
+use log::{debug, error, info, warn};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Start a RESP2 (Redis serialization protocol) server backed by the given cache.
+///
+/// Accepts TCP connections, parses RESP arrays of bulk strings, dispatches
+/// `GET`/`SET`/`DEL`/`EXISTS`/`PING` against the shared `ApiCache`, and writes
+/// back RESP-encoded replies.
+pub async fn start_server<T: ApiCache + 'static>(
+    cache: Arc<T>,
+    host: String,
+    port: u16,
+) -> Result<(), std::io::Error> {
+    let addr: SocketAddr = format!("{}:{}", host, port)
+        .parse()
+        .expect("Invalid RESP server address");
+
+    let listener = TcpListener::bind(addr).await?;
+    info!("RESP server listening on {}", addr);
+
+    loop {
+        let (stream, peer_addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                error!("Failed to accept RESP connection: {}", e);
+                continue;
+            }
+        };
+
+        debug!("Accepted RESP connection from {}", peer_addr);
+        let cache = Arc::clone(&cache);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, cache).await {
+                warn!("RESP connection from {} closed with error: {}", peer_addr, e);
+            }
+        });
+    }
+}
+
+/// Cap on a RESP bulk string's declared length, applied before allocating a
+/// buffer for it. Matches Redis's own default `proto-max-bulk-len` of 512MB;
+/// without it a connection can declare a multi-gigabyte length and trigger a
+/// huge allocation before a single byte of the payload has been read.
+const MAX_BULK_LEN: i64 = 512 * 1024 * 1024;
+
+/// Cap on a RESP array's declared element count, applied before allocating
+/// storage for it. Mirrors `MAX_BULK_LEN`'s reasoning: without it a
+/// connection can declare a multi-billion-element array and trigger a huge
+/// allocation (or an overflowing one) before a single further byte is read.
+/// Redis itself has no single named constant for this, but rejects absurd
+/// multibulk counts outright; 1M elements is far beyond any real command.
+const MAX_ARRAY_LEN: i64 = 1024 * 1024;
+
+async fn handle_connection<T: ApiCache + 'static>(
+    stream: TcpStream,
+    cache: Arc<T>,
+) -> Result<(), std::io::Error> {
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let command = match read_command(&mut reader).await? {
+            Some(command) => command,
+            None => return Ok(()),
+        };
+
+        if command.is_empty() {
+            continue;
+        }
+
+        let reply = dispatch(&cache, &command).await;
+        reader.get_mut().write_all(&reply).await?;
+    }
+}
+
+/// Read one RESP array-of-bulk-strings command, e.g. `*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n`.
+/// Returns `Ok(None)` on a clean EOF between commands.
+async fn read_command<R: AsyncReadExt + Unpin>(
+    reader: &mut R,
+) -> Result<Option<Vec<String>>, std::io::Error> {
+    let header = match read_line(reader).await? {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    if !header.starts_with('*') {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("expected RESP array, got: {}", header),
+        ));
+    }
+
+    let count: i64 = header[1..].parse().map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid array length")
+    })?;
+
+    if count > MAX_ARRAY_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("array length {} exceeds maximum of {}", count, MAX_ARRAY_LEN),
+        ));
+    }
+
+    let mut parts = Vec::with_capacity(count.max(0) as usize);
+    for _ in 0..count {
+        let len_line = read_line(reader)
+            .await?
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "truncated RESP frame"))?;
+
+        if !len_line.starts_with('$') {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("expected RESP bulk string, got: {}", len_line),
+            ));
+        }
+
+        let len: i64 = len_line[1..].parse().map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid bulk string length")
+        })?;
+
+        if len > MAX_BULK_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("bulk string length {} exceeds maximum of {}", len, MAX_BULK_LEN),
+            ));
+        }
+
+        let mut buf = vec![0u8; len.max(0) as usize];
+        reader.read_exact(&mut buf).await?;
+
+        // Consume the trailing \r\n after the bulk string payload
+        let mut crlf = [0u8; 2];
+        reader.read_exact(&mut crlf).await?;
+
+        parts.push(String::from_utf8_lossy(&buf).into_owned());
+    }
+
+    Ok(Some(parts))
+}
+
+async fn read_line<R: AsyncReadExt + Unpin>(
+    reader: &mut R,
+) -> Result<Option<String>, std::io::Error> {
+    let mut line = Vec::new();
+    let mut byte = [0u8; 1];
+
+    loop {
+        let n = reader.read(&mut byte).await?;
+        if n == 0 {
+            if line.is_empty() {
+                return Ok(None);
+            }
+            break;
+        }
+
+        if byte[0] == b'\n' {
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            break;
+        }
+
+        line.push(byte[0]);
+    }
+
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+async fn dispatch<T: ApiCache + 'static>(cache: &Arc<T>, command: &[String]) -> Vec<u8> {
+    let name = command[0].to_ascii_uppercase();
+    match name.as_str() {
+        "PING" => simple_string("PONG"),
+        "GET" => {
+            if command.len() != 2 {
+                return error_reply("wrong number of arguments for 'get' command");
+            }
+            match cache.get(&command[1]).await {
+                Some(value) => bulk_string(&value),
+                None => null_bulk_string(),
+            }
+        }
+        "SET" => {
+            if command.len() != 3 {
+                return error_reply("wrong number of arguments for 'set' command");
+            }
+            match cache.set(command[1].clone(), command[2].clone()).await {
+                Ok(_) => simple_string("OK"),
+                Err(e) => error_reply(&format!("failed to set key: {}", e)),
+            }
+        }
+        "DEL" => {
+            if command.len() != 2 {
+                return error_reply("wrong number of arguments for 'del' command");
+            }
+            match cache.del(&command[1]).await {
+                Ok(true) => integer(1),
+                Ok(false) => integer(0),
+                Err(e) => error_reply(&format!("failed to delete key: {}", e)),
+            }
+        }
+        "EXISTS" => {
+            if command.len() != 2 {
+                return error_reply("wrong number of arguments for 'exists' command");
+            }
+            if cache.exists(&command[1]).await {
+                integer(1)
+            } else {
+                integer(0)
+            }
+        }
+        other => error_reply(&format!("unknown command '{}'", other)),
+    }
+}
+
+fn simple_string(s: &str) -> Vec<u8> {
+    format!("+{}\r\n", s).into_bytes()
+}
+
+fn bulk_string(s: &str) -> Vec<u8> {
+    format!("${}\r\n{}\r\n", s.len(), s).into_bytes()
+}
+
+fn null_bulk_string() -> Vec<u8> {
+    b"$-1\r\n".to_vec()
+}
+
+fn integer(n: i64) -> Vec<u8> {
+    format!(":{}\r\n", n).into_bytes()
+}
+
+fn error_reply(message: &str) -> Vec<u8> {
+    format!("-ERR {}\r\n", message).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{EvictionPolicy, FastbuCache};
+    use std::io::Cursor;
+
+    fn cache() -> Arc<FastbuCache> {
+        Arc::new(FastbuCache::new(EvictionPolicy::Lru, usize::MAX))
+    }
+
+    #[tokio::test]
+    async fn test_read_command_parses_array_of_bulk_strings() {
+        let mut reader = Cursor::new(b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n".to_vec());
+        let command = read_command(&mut reader).await.unwrap().unwrap();
+        assert_eq!(command, vec!["GET".to_string(), "foo".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_read_command_returns_none_on_clean_eof() {
+        let mut reader = Cursor::new(Vec::new());
+        assert!(read_command(&mut reader).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_command_rejects_non_array_input() {
+        let mut reader = Cursor::new(b"PING\r\n".to_vec());
+        let err = read_command(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_read_command_rejects_garbage_array_length() {
+        let mut reader = Cursor::new(b"*abc\r\n".to_vec());
+        let err = read_command(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_read_command_rejects_oversized_array_length() {
+        let mut reader = Cursor::new(b"*999999999999\r\n".to_vec());
+        let err = read_command(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_read_command_rejects_garbage_bulk_string_length() {
+        let mut reader = Cursor::new(b"*1\r\n$abc\r\n".to_vec());
+        let err = read_command(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_read_command_clamps_negative_bulk_string_length_to_empty() {
+        // A negative length isn't rejected outright -- `len.max(0)` clamps it
+        // to an empty bulk string rather than erroring.
+        let mut reader = Cursor::new(b"*1\r\n$-5\r\n\r\n".to_vec());
+        let command = read_command(&mut reader).await.unwrap().unwrap();
+        assert_eq!(command, vec!["".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_read_command_errors_on_truncated_frame() {
+        let mut reader = Cursor::new(b"*1\r\n$3\r\nfo".to_vec());
+        let err = read_command(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_ping() {
+        let reply = dispatch(&cache(), &["PING".to_string()]).await;
+        assert_eq!(reply, b"+PONG\r\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_get_set_roundtrip() {
+        let cache = cache();
+        let set_reply = dispatch(&cache, &["SET".to_string(), "k".to_string(), "v".to_string()]).await;
+        assert_eq!(set_reply, b"+OK\r\n".to_vec());
+
+        let get_reply = dispatch(&cache, &["GET".to_string(), "k".to_string()]).await;
+        assert_eq!(get_reply, b"$1\r\nv\r\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_get_missing_key_returns_null_bulk_string() {
+        let reply = dispatch(&cache(), &["GET".to_string(), "missing".to_string()]).await;
+        assert_eq!(reply, b"$-1\r\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_wrong_arity_returns_error_reply() {
+        let reply = dispatch(&cache(), &["GET".to_string()]).await;
+        assert!(reply.starts_with(b"-ERR"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_unknown_command_returns_error_reply() {
+        let reply = dispatch(&cache(), &["FOOBAR".to_string()]).await;
+        assert_eq!(reply, b"-ERR unknown command 'FOOBAR'\r\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_del_returns_zero_for_missing_key() {
+        let reply = dispatch(&cache(), &["DEL".to_string(), "missing".to_string()]).await;
+        assert_eq!(reply, b":0\r\n".to_vec());
+    }
+}
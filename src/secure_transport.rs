@@ -0,0 +1,801 @@
+//! Authenticated, encrypted transport for node-to-node connections.
+//!
+//! Each node has a long-lived ed25519 keypair; `Node.id` is the hex encoding
+//! of its public key. A cluster-wide pre-shared network key additionally
+//! gates membership: both sides of a connection must prove knowledge of it.
+//! The handshake (modeled on a Noise/secret-handshake exchange) derives a
+//! session key via X25519 + HKDF and wraps the stream in a `SecureChannel`
+//! that encrypts every subsequent frame with ChaCha20-Poly1305. Alongside the
+//! handshake payload, both sides also exchange a byte advertising which
+//! compression codecs they support; frames above a size threshold are then
+//! compressed under whichever codec both ends have in common.
+
+use crate::cluster::{ClusterError, NodeAddr};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use log::{debug, warn};
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::tcp::{OwnedReadHalf as TcpReadHalf, OwnedWriteHalf as TcpWriteHalf};
+use tokio::net::unix::{OwnedReadHalf as UnixReadHalf, OwnedWriteHalf as UnixWriteHalf};
+use tokio::net::{TcpStream, UnixStream};
+use x25519_dalek::{EphemeralSecret, PublicKey as X25519PublicKey};
+
+type ClusterResult<T> = Result<T, ClusterError>;
+
+/// Either side of a node-to-node connection: a TCP socket, or (for
+/// co-located nodes) a Unix domain socket. Everything above this layer —
+/// the handshake, `SecureChannel`'s framing — is written against
+/// `AsyncRead + AsyncWrite` and doesn't care which it got.
+pub enum Transport {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+}
+
+impl Transport {
+    /// Dial `addr`, picking the underlying socket type to match
+    pub async fn connect(addr: &NodeAddr) -> std::io::Result<Self> {
+        match addr {
+            NodeAddr::Tcp(socket_addr) => Ok(Transport::Tcp(TcpStream::connect(socket_addr).await?)),
+            NodeAddr::Unix(path) => Ok(Transport::Unix(UnixStream::connect(path).await?)),
+        }
+    }
+
+    fn into_split(self) -> (TransportReadHalf, TransportWriteHalf) {
+        match self {
+            Transport::Tcp(stream) => {
+                let (read, write) = stream.into_split();
+                (TransportReadHalf::Tcp(read), TransportWriteHalf::Tcp(write))
+            }
+            Transport::Unix(stream) => {
+                let (read, write) = stream.into_split();
+                (TransportReadHalf::Unix(read), TransportWriteHalf::Unix(write))
+            }
+        }
+    }
+}
+
+impl AsyncRead for Transport {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            Transport::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Transport {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            Transport::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            Transport::Unix(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Transport::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            Transport::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// The read half of a split `Transport`
+enum TransportReadHalf {
+    Tcp(TcpReadHalf),
+    Unix(UnixReadHalf),
+}
+
+impl AsyncRead for TransportReadHalf {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            TransportReadHalf::Tcp(half) => Pin::new(half).poll_read(cx, buf),
+            TransportReadHalf::Unix(half) => Pin::new(half).poll_read(cx, buf),
+        }
+    }
+}
+
+/// The write half of a split `Transport`
+enum TransportWriteHalf {
+    Tcp(TcpWriteHalf),
+    Unix(UnixWriteHalf),
+}
+
+impl AsyncWrite for TransportWriteHalf {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            TransportWriteHalf::Tcp(half) => Pin::new(half).poll_write(cx, buf),
+            TransportWriteHalf::Unix(half) => Pin::new(half).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            TransportWriteHalf::Tcp(half) => Pin::new(half).poll_flush(cx),
+            TransportWriteHalf::Unix(half) => Pin::new(half).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            TransportWriteHalf::Tcp(half) => Pin::new(half).poll_shutdown(cx),
+            TransportWriteHalf::Unix(half) => Pin::new(half).poll_shutdown(cx),
+        }
+    }
+}
+
+/// A node's long-lived ed25519 identity. `Node.id` is derived from this as
+/// `hex(verifying_key)`, so authenticating a peer's claimed id is just
+/// checking it against the key it actually signs with.
+pub struct NodeIdentity {
+    signing_key: SigningKey,
+}
+
+impl NodeIdentity {
+    /// Generate a fresh, random identity. Callers that want a stable node
+    /// id across restarts should persist the signing key themselves.
+    pub fn generate() -> Self {
+        Self {
+            signing_key: SigningKey::generate(&mut OsRng),
+        }
+    }
+
+    /// Restore an identity from a previously persisted 32-byte secret seed
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(seed),
+        }
+    }
+
+    /// The raw 32-byte secret seed, for persistence
+    pub fn seed(&self) -> [u8; 32] {
+        self.signing_key.to_bytes()
+    }
+
+    /// Load the identity's seed from `path`, or generate a fresh one and
+    /// write it there if the file doesn't exist yet. A missing file is the
+    /// expected first-run case; an unreadable or malformed one falls back to
+    /// a fresh identity rather than failing startup, same as `NodeTable::load`.
+    pub fn load_or_generate(path: &str) -> Self {
+        match std::fs::read(path) {
+            Ok(bytes) => match <[u8; 32]>::try_from(bytes.as_slice()) {
+                Ok(seed) => return Self::from_seed(&seed),
+                Err(_) => {
+                    warn!("Identity file {} is not a valid 32-byte seed; generating a new identity", path);
+                }
+            },
+            Err(e) => {
+                debug!("No existing identity at {} ({}); generating a new one", path, e);
+            }
+        }
+
+        let identity = Self::generate();
+        if let Err(e) = std::fs::write(path, identity.seed()) {
+            warn!("Failed to persist identity to {}: {}", path, e);
+        }
+        identity
+    }
+
+    /// The hex-encoded ed25519 public key; used as this node's `Node.id`
+    pub fn node_id(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().to_bytes())
+    }
+}
+
+/// Fixed-size handshake payload exchanged by both sides before any
+/// `ClusterMessage` is sent: an ephemeral X25519 public key for this
+/// connection, the sender's long-lived ed25519 public key, a signature over
+/// the ephemeral key (proves possession of the ed25519 secret), and an HMAC
+/// over both keys keyed by the cluster's pre-shared network key (proves
+/// membership in the cluster).
+struct HandshakePayload {
+    ephemeral_public: [u8; 32],
+    node_public: [u8; 32],
+    signature: [u8; 64],
+    network_proof: [u8; 32],
+}
+
+const HANDSHAKE_PAYLOAD_LEN: usize = 32 + 32 + 64 + 32;
+
+impl HandshakePayload {
+    fn to_bytes(&self) -> [u8; HANDSHAKE_PAYLOAD_LEN] {
+        let mut bytes = [0u8; HANDSHAKE_PAYLOAD_LEN];
+        bytes[0..32].copy_from_slice(&self.ephemeral_public);
+        bytes[32..64].copy_from_slice(&self.node_public);
+        bytes[64..128].copy_from_slice(&self.signature);
+        bytes[128..160].copy_from_slice(&self.network_proof);
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; HANDSHAKE_PAYLOAD_LEN]) -> Self {
+        let mut ephemeral_public = [0u8; 32];
+        let mut node_public = [0u8; 32];
+        let mut signature = [0u8; 64];
+        let mut network_proof = [0u8; 32];
+        ephemeral_public.copy_from_slice(&bytes[0..32]);
+        node_public.copy_from_slice(&bytes[32..64]);
+        signature.copy_from_slice(&bytes[64..128]);
+        network_proof.copy_from_slice(&bytes[128..160]);
+        Self {
+            ephemeral_public,
+            node_public,
+            signature,
+            network_proof,
+        }
+    }
+}
+
+fn network_proof(network_key: &[u8], ephemeral_public: &[u8; 32], node_public: &[u8; 32]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(network_key).expect("HMAC accepts keys of any length");
+    mac.update(ephemeral_public);
+    mac.update(node_public);
+    mac.finalize().into_bytes().into()
+}
+
+fn build_payload(identity: &NodeIdentity, network_key: &[u8], ephemeral_public: &X25519PublicKey) -> HandshakePayload {
+    let ephemeral_bytes = *ephemeral_public.as_bytes();
+    let node_public = identity.signing_key.verifying_key().to_bytes();
+    let signature: Signature = identity.signing_key.sign(&ephemeral_bytes);
+    let proof = network_proof(network_key, &ephemeral_bytes, &node_public);
+    HandshakePayload {
+        ephemeral_public: ephemeral_bytes,
+        node_public,
+        signature: signature.to_bytes(),
+        network_proof: proof,
+    }
+}
+
+/// Verify a peer's handshake payload: its claimed id matches its ed25519
+/// key, it actually holds that key's secret, and it knows the network key.
+/// Returns the peer's node id (hex of its public key) on success.
+fn verify_payload(payload: &HandshakePayload, network_key: &[u8], expected_peer_id: Option<&str>) -> ClusterResult<String> {
+    let peer_id = hex::encode(payload.node_public);
+    if let Some(expected) = expected_peer_id {
+        if expected != peer_id {
+            return Err(ClusterError::HandshakeError(format!(
+                "Peer presented id {} but we dialed {}", peer_id, expected
+            )));
+        }
+    }
+
+    let verifying_key = VerifyingKey::from_bytes(&payload.node_public).map_err(|e| {
+        ClusterError::HandshakeError(format!("Peer presented an invalid ed25519 public key: {}", e))
+    })?;
+    let signature = Signature::from_bytes(&payload.signature);
+    verifying_key.verify(&payload.ephemeral_public, &signature).map_err(|e| {
+        ClusterError::HandshakeError(format!("Peer failed to prove possession of its ed25519 key: {}", e))
+    })?;
+
+    let expected_proof = network_proof(network_key, &payload.ephemeral_public, &payload.node_public);
+    if expected_proof != payload.network_proof {
+        return Err(ClusterError::HandshakeError(
+            "Peer does not know the cluster network key".to_string(),
+        ));
+    }
+
+    Ok(peer_id)
+}
+
+/// Payload compression applied above `COMPRESSION_THRESHOLD`, negotiated as
+/// one extra byte alongside the handshake payload and then fixed for the
+/// channel's lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompressionCodec {
+    /// Frames are sent as-is.
+    None,
+    /// Frames are compressed with zstd at its default level.
+    Zstd,
+}
+
+/// Bitmask of compression codecs this build supports, exchanged as one extra
+/// byte alongside the handshake payload. `None` needs no flag of its own —
+/// it's always supported — so today this is just the zstd bit, leaving room
+/// to grow if more codecs are added later.
+const CODEC_FLAG_ZSTD: u8 = 0b0000_0001;
+
+fn supported_codec_flags() -> u8 {
+    CODEC_FLAG_ZSTD
+}
+
+/// The best codec both sides advertised, preferring zstd over the no-op.
+fn negotiate_codec(local_flags: u8, peer_flags: u8) -> CompressionCodec {
+    if local_flags & peer_flags & CODEC_FLAG_ZSTD != 0 {
+        CompressionCodec::Zstd
+    } else {
+        CompressionCodec::None
+    }
+}
+
+/// Frames at or above this size are compressed, so the handful of small,
+/// latency-sensitive messages (heartbeats, single-key fetches) skip
+/// compression overhead and only bulk payloads benefit.
+const COMPRESSION_THRESHOLD: usize = 4096;
+
+/// Wrap `plaintext` for the wire ahead of encryption: compress it under
+/// `codec` when it's large enough to be worth it, prefixed with a tag byte
+/// (and, for compressed frames, the original length) so the receiving side
+/// knows how to undo it regardless of what this particular frame did.
+fn encode_frame(plaintext: &[u8], codec: CompressionCodec) -> Vec<u8> {
+    if codec == CompressionCodec::Zstd && plaintext.len() >= COMPRESSION_THRESHOLD {
+        if let Ok(compressed) = zstd::bulk::compress(plaintext, 0) {
+            let mut framed = Vec::with_capacity(compressed.len() + 5);
+            framed.push(1u8);
+            framed.extend_from_slice(&(plaintext.len() as u32).to_be_bytes());
+            framed.extend_from_slice(&compressed);
+            return framed;
+        }
+    }
+
+    let mut framed = Vec::with_capacity(plaintext.len() + 1);
+    framed.push(0u8);
+    framed.extend_from_slice(plaintext);
+    framed
+}
+
+/// Undo `encode_frame`: decompress the payload if the sender tagged the
+/// frame as compressed, otherwise return it unchanged. `max_frame_size`
+/// bounds the sender-declared decompressed length the same way it already
+/// bounds the ciphertext length in `recv_frame` -- without it a small,
+/// well-formed compressed frame could claim a decompressed size up to
+/// `u32::MAX` and force a multi-gigabyte allocation in `zstd::bulk::decompress`.
+fn decode_frame(framed: Vec<u8>, max_frame_size: usize) -> ClusterResult<Vec<u8>> {
+    let (tag, rest) = framed
+        .split_first()
+        .ok_or_else(|| ClusterError::CommunicationError("Received an empty frame".to_string()))?;
+    match tag {
+        1 => {
+            if rest.len() < 4 {
+                return Err(ClusterError::CommunicationError(
+                    "Compressed frame is missing its length prefix".to_string(),
+                ));
+            }
+            let (len_bytes, payload) = rest.split_at(4);
+            let original_len = u32::from_be_bytes(len_bytes.try_into().expect("split_at(4) gives a 4-byte slice")) as usize;
+            if original_len > max_frame_size {
+                return Err(ClusterError::FrameTooLarge { len: original_len, max: max_frame_size });
+            }
+            zstd::bulk::decompress(payload, original_len)
+                .map_err(|e| ClusterError::CommunicationError(format!("Failed to decompress frame: {}", e)))
+        }
+        _ => Ok(rest.to_vec()),
+    }
+}
+
+fn derive_session_keys(shared_secret: &[u8], is_dialer: bool) -> ([u8; 32], [u8; 32]) {
+    let hk = Hkdf::<Sha256>::new(None, shared_secret);
+    let mut dialer_key = [0u8; 32];
+    let mut listener_key = [0u8; 32];
+    hk.expand(b"fastbu-handshake-dialer", &mut dialer_key)
+        .expect("32 bytes is a valid HKDF output length");
+    hk.expand(b"fastbu-handshake-listener", &mut listener_key)
+        .expect("32 bytes is a valid HKDF output length");
+
+    if is_dialer {
+        (dialer_key, listener_key)
+    } else {
+        (listener_key, dialer_key)
+    }
+}
+
+/// A transport stream wrapped in a post-handshake session: every frame is
+/// encrypted with ChaCha20-Poly1305 under a direction-specific key, with a
+/// monotonically increasing nonce counter per direction.
+pub struct SecureChannel {
+    stream: Transport,
+    peer_id: String,
+    send_cipher: ChaCha20Poly1305,
+    recv_cipher: ChaCha20Poly1305,
+    send_nonce: u64,
+    recv_nonce: u64,
+    max_frame_size: usize,
+    compression: CompressionCodec,
+}
+
+fn nonce_for(counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..12].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+impl SecureChannel {
+    /// The authenticated node id of the peer on the other end of this channel
+    pub fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    /// Encrypt and send one length-prefixed frame, compressing the payload
+    /// first if the negotiated codec and the payload's size call for it.
+    pub async fn send_frame(&mut self, plaintext: &[u8]) -> ClusterResult<()> {
+        let framed = encode_frame(plaintext, self.compression);
+        let nonce = nonce_for(self.send_nonce);
+        self.send_nonce += 1;
+
+        let ciphertext = self
+            .send_cipher
+            .encrypt(Nonce::from_slice(&nonce), framed.as_slice())
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to encrypt frame: {}", e)))?;
+
+        let len = ciphertext.len() as u32;
+        self.stream
+            .write_all(&len.to_be_bytes())
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to send frame length: {}", e)))?;
+        self.stream
+            .write_all(&ciphertext)
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to send frame data: {}", e)))?;
+        Ok(())
+    }
+
+    /// Receive and decrypt one length-prefixed frame, decompressing it first
+    /// if the sender tagged it as such.
+    pub async fn recv_frame(&mut self) -> ClusterResult<Vec<u8>> {
+        let mut len_bytes = [0u8; 4];
+        self.stream
+            .read_exact(&mut len_bytes)
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to read frame length: {}", e)))?;
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        if len > self.max_frame_size {
+            return Err(ClusterError::FrameTooLarge { len, max: self.max_frame_size });
+        }
+
+        let mut ciphertext = vec![0u8; len];
+        self.stream
+            .read_exact(&mut ciphertext)
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to read frame data: {}", e)))?;
+
+        let nonce = nonce_for(self.recv_nonce);
+        self.recv_nonce += 1;
+        let framed = self
+            .recv_cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to decrypt frame: {}", e)))?;
+        decode_frame(framed, self.max_frame_size)
+    }
+
+    /// Split into independent read/write halves so a connection can be
+    /// multiplexed: one task can block draining replies off the reader
+    /// while sends go out through the writer without waiting on it.
+    pub fn into_split(self) -> (SecureChannelReader, SecureChannelWriter) {
+        let (read_half, write_half) = self.stream.into_split();
+        (
+            SecureChannelReader {
+                stream: read_half,
+                peer_id: self.peer_id.clone(),
+                recv_cipher: self.recv_cipher,
+                recv_nonce: self.recv_nonce,
+                max_frame_size: self.max_frame_size,
+            },
+            SecureChannelWriter {
+                stream: write_half,
+                peer_id: self.peer_id,
+                send_cipher: self.send_cipher,
+                send_nonce: self.send_nonce,
+                compression: self.compression,
+            },
+        )
+    }
+}
+
+/// The read half of a split `SecureChannel`
+pub struct SecureChannelReader {
+    stream: TransportReadHalf,
+    peer_id: String,
+    recv_cipher: ChaCha20Poly1305,
+    recv_nonce: u64,
+    max_frame_size: usize,
+}
+
+impl SecureChannelReader {
+    pub fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    /// Receive and decrypt one length-prefixed frame, decompressing it first
+    /// if the sender tagged it as such.
+    pub async fn recv_frame(&mut self) -> ClusterResult<Vec<u8>> {
+        let mut len_bytes = [0u8; 4];
+        self.stream
+            .read_exact(&mut len_bytes)
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to read frame length: {}", e)))?;
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        if len > self.max_frame_size {
+            return Err(ClusterError::FrameTooLarge { len, max: self.max_frame_size });
+        }
+
+        let mut ciphertext = vec![0u8; len];
+        self.stream
+            .read_exact(&mut ciphertext)
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to read frame data: {}", e)))?;
+
+        let nonce = nonce_for(self.recv_nonce);
+        self.recv_nonce += 1;
+        let framed = self
+            .recv_cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to decrypt frame: {}", e)))?;
+        decode_frame(framed, self.max_frame_size)
+    }
+}
+
+/// The write half of a split `SecureChannel`
+pub struct SecureChannelWriter {
+    stream: TransportWriteHalf,
+    peer_id: String,
+    send_cipher: ChaCha20Poly1305,
+    send_nonce: u64,
+    compression: CompressionCodec,
+}
+
+impl SecureChannelWriter {
+    pub fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    /// Encrypt and send one length-prefixed frame, compressing the payload
+    /// first if the negotiated codec and the payload's size call for it.
+    pub async fn send_frame(&mut self, plaintext: &[u8]) -> ClusterResult<()> {
+        let framed = encode_frame(plaintext, self.compression);
+        let nonce = nonce_for(self.send_nonce);
+        self.send_nonce += 1;
+
+        let ciphertext = self
+            .send_cipher
+            .encrypt(Nonce::from_slice(&nonce), framed.as_slice())
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to encrypt frame: {}", e)))?;
+
+        let len = ciphertext.len() as u32;
+        self.stream
+            .write_all(&len.to_be_bytes())
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to send frame length: {}", e)))?;
+        self.stream
+            .write_all(&ciphertext)
+            .await
+            .map_err(|e| ClusterError::CommunicationError(format!("Failed to send frame data: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Run the client side of the handshake over a freshly connected `stream`,
+/// authenticating ourselves and (if `expected_peer_id` is given) verifying
+/// the peer is who we meant to dial. `max_frame_size` bounds how large a
+/// declared frame length the resulting channel will accept before
+/// allocating a buffer for it.
+pub async fn client_handshake(
+    mut stream: Transport,
+    identity: &NodeIdentity,
+    network_key: &[u8],
+    expected_peer_id: Option<&str>,
+    max_frame_size: usize,
+) -> ClusterResult<SecureChannel> {
+    let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = X25519PublicKey::from(&ephemeral_secret);
+
+    let our_payload = build_payload(identity, network_key, &ephemeral_public);
+    stream
+        .write_all(&our_payload.to_bytes())
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to send handshake: {}", e)))?;
+    stream
+        .write_all(&[supported_codec_flags()])
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to send codec flags: {}", e)))?;
+
+    let mut peer_bytes = [0u8; HANDSHAKE_PAYLOAD_LEN];
+    stream
+        .read_exact(&mut peer_bytes)
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to read peer handshake: {}", e)))?;
+    let peer_payload = HandshakePayload::from_bytes(&peer_bytes);
+    let peer_id = verify_payload(&peer_payload, network_key, expected_peer_id)?;
+
+    let mut peer_codec_flags = [0u8; 1];
+    stream
+        .read_exact(&mut peer_codec_flags)
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to read peer codec flags: {}", e)))?;
+    let compression = negotiate_codec(supported_codec_flags(), peer_codec_flags[0]);
+
+    let peer_ephemeral = X25519PublicKey::from(peer_payload.ephemeral_public);
+    let shared_secret = ephemeral_secret.diffie_hellman(&peer_ephemeral);
+    let (send_key, recv_key) = derive_session_keys(shared_secret.as_bytes(), true);
+
+    Ok(SecureChannel {
+        stream,
+        peer_id,
+        send_cipher: ChaCha20Poly1305::new(Key::from_slice(&send_key)),
+        recv_cipher: ChaCha20Poly1305::new(Key::from_slice(&recv_key)),
+        send_nonce: 0,
+        recv_nonce: 0,
+        max_frame_size,
+        compression,
+    })
+}
+
+/// Run the server side of the handshake over a freshly accepted `stream`.
+/// `max_frame_size` bounds how large a declared frame length the resulting
+/// channel will accept before allocating a buffer for it.
+pub async fn server_handshake(
+    mut stream: Transport,
+    identity: &NodeIdentity,
+    network_key: &[u8],
+    max_frame_size: usize,
+) -> ClusterResult<SecureChannel> {
+    let mut peer_bytes = [0u8; HANDSHAKE_PAYLOAD_LEN];
+    stream
+        .read_exact(&mut peer_bytes)
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to read peer handshake: {}", e)))?;
+    let peer_payload = HandshakePayload::from_bytes(&peer_bytes);
+    let peer_id = verify_payload(&peer_payload, network_key, None)?;
+
+    let mut peer_codec_flags = [0u8; 1];
+    stream
+        .read_exact(&mut peer_codec_flags)
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to read peer codec flags: {}", e)))?;
+    let compression = negotiate_codec(supported_codec_flags(), peer_codec_flags[0]);
+
+    let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = X25519PublicKey::from(&ephemeral_secret);
+    let our_payload = build_payload(identity, network_key, &ephemeral_public);
+    stream
+        .write_all(&our_payload.to_bytes())
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to send handshake: {}", e)))?;
+    stream
+        .write_all(&[supported_codec_flags()])
+        .await
+        .map_err(|e| ClusterError::HandshakeError(format!("Failed to send codec flags: {}", e)))?;
+
+    let peer_ephemeral = X25519PublicKey::from(peer_payload.ephemeral_public);
+    let shared_secret = ephemeral_secret.diffie_hellman(&peer_ephemeral);
+    let (send_key, recv_key) = derive_session_keys(shared_secret.as_bytes(), false);
+
+    Ok(SecureChannel {
+        stream,
+        peer_id,
+        send_cipher: ChaCha20Poly1305::new(Key::from_slice(&send_key)),
+        recv_cipher: ChaCha20Poly1305::new(Key::from_slice(&recv_key)),
+        send_nonce: 0,
+        recv_nonce: 0,
+        max_frame_size,
+        compression,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_id_matches_verifying_key() {
+        let identity = NodeIdentity::generate();
+        let expected = hex::encode(identity.signing_key.verifying_key().to_bytes());
+        assert_eq!(identity.node_id(), expected);
+    }
+
+    #[test]
+    fn test_identity_roundtrips_through_seed() {
+        let identity = NodeIdentity::generate();
+        let restored = NodeIdentity::from_seed(&identity.seed());
+        assert_eq!(identity.node_id(), restored.node_id());
+    }
+
+    #[test]
+    fn test_load_or_generate_persists_and_reloads_same_identity() {
+        let path = std::env::temp_dir().join(format!("fastbu-test-identity-{}.key", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let first = NodeIdentity::load_or_generate(path);
+        let second = NodeIdentity::load_or_generate(path);
+        assert_eq!(first.node_id(), second.node_id());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_verify_payload_rejects_wrong_network_key() {
+        let identity = NodeIdentity::generate();
+        let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = X25519PublicKey::from(&ephemeral_secret);
+        let payload = build_payload(&identity, b"correct-key", &ephemeral_public);
+
+        assert!(verify_payload(&payload, b"correct-key", None).is_ok());
+        assert!(verify_payload(&payload, b"wrong-key", None).is_err());
+    }
+
+    #[test]
+    fn test_verify_payload_rejects_mismatched_expected_id() {
+        let identity = NodeIdentity::generate();
+        let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = X25519PublicKey::from(&ephemeral_secret);
+        let payload = build_payload(&identity, b"network-key", &ephemeral_public);
+
+        assert!(verify_payload(&payload, b"network-key", Some("not-the-right-id")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recv_frame_rejects_frame_over_max_size() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server_identity = NodeIdentity::generate();
+        let client_identity = NodeIdentity::generate();
+
+        let server_task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            // A tiny cap so an otherwise-ordinary frame still trips it.
+            let mut channel = server_handshake(Transport::Tcp(stream), &server_identity, b"", 8)
+                .await
+                .unwrap();
+            channel.recv_frame().await
+        });
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut client_channel = client_handshake(
+            Transport::Tcp(stream), &client_identity, b"", None, 1024 * 1024,
+        ).await.unwrap();
+        client_channel.send_frame(b"this payload is longer than eight bytes").await.unwrap();
+
+        let result = server_task.await.unwrap();
+        assert!(matches!(result, Err(ClusterError::FrameTooLarge { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_large_frame_round_trips_under_negotiated_compression() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server_identity = NodeIdentity::generate();
+        let client_identity = NodeIdentity::generate();
+
+        // Compressible (lots of repetition) and comfortably over
+        // COMPRESSION_THRESHOLD, so this frame actually exercises the
+        // negotiated codec rather than passing straight through.
+        let payload = "fastbu-round-trip-".repeat(1000);
+        let payload_bytes = payload.as_bytes().to_vec();
+
+        let server_payload = payload_bytes.clone();
+        let server_task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut channel = server_handshake(Transport::Tcp(stream), &server_identity, b"", 1024 * 1024)
+                .await
+                .unwrap();
+            let received = channel.recv_frame().await.unwrap();
+            assert_eq!(received, server_payload);
+        });
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut client_channel = client_handshake(
+            Transport::Tcp(stream), &client_identity, b"", None, 1024 * 1024,
+        ).await.unwrap();
+        assert_eq!(client_channel.compression, CompressionCodec::Zstd);
+        client_channel.send_frame(&payload_bytes).await.unwrap();
+
+        server_task.await.unwrap();
+    }
+}
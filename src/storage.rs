@@ -3,17 +3,64 @@ use chrono::{DateTime, Utc};
 use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
-use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex}; // Add Arc and Mutex for thread-safe access
 
 const STORAGE_DIR: &str = "cache_storage";
 const INDEX_FILE: &str = "cache_index.bin";
 
+/// Disk-persistence tier behind `FastbuCache`. Swappable so the bounded
+/// in-memory tier can fall back to whichever durable store fits the
+/// deployment (the append-only `FileStorage` for a single instance, or
+/// `RocksDbStorage` for a crash-consistent embedded store).
+pub trait StorageBackend: Send + Sync {
+    fn save(&self, key: &str, entry: &CacheEntry) -> io::Result<()>;
+
+    /// Returns `Ok(None)` if `key` was never saved.
+    fn load(&self, key: &str) -> io::Result<Option<CacheEntry>>;
+
+    /// Returns whether `key` had a persisted entry to remove.
+    fn delete(&self, key: &str) -> io::Result<bool>;
+
+    /// All keys currently persisted, for the background expiration sweep to
+    /// scan entries that are no longer in the memory tier.
+    fn keys(&self) -> Vec<String>;
+}
+
+/// Distinguishes logical value-types sharing one physical keyspace (e.g. a
+/// single RocksDB instance), so a future subsystem storing cluster metadata
+/// or index snapshots alongside cache entries can't collide with them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum CacheKind {
+    Entry = 1,
+}
+
+/// Keys handed to a `StorageBackend` are namespaced as a one-byte kind
+/// prefix followed by the key's raw bytes before they ever reach the
+/// backend's actual storage.
+fn prefixed_key(kind: CacheKind, key: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(1 + key.len());
+    bytes.push(kind as u8);
+    bytes.extend_from_slice(key.as_bytes());
+    bytes
+}
+
+/// Hex-encodes a prefixed key into a filesystem-safe filename stem.
+fn prefixed_key_hex(kind: CacheKind, key: &str) -> String {
+    prefixed_key(kind, key).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct StorageMetadata {
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    /// Byte offset into the file where the most recently written record
+    /// starts. The file is append-only, so earlier writes of the same key
+    /// are still sitting before this offset until the next `compact()`.
+    offset: u64,
+    /// Total file size after the most recent write.
     size: u64,
 }
 
@@ -24,15 +71,16 @@ struct IndexEntry {
     metadata: StorageMetadata,
 }
 
-pub struct Storage {
+/// Append-only, one-file-per-key disk backend. The default `StorageBackend`.
+pub struct FileStorage {
     base_dir: PathBuf,
     index_file: PathBuf,
     index: Arc<Mutex<Vec<IndexEntry>>>,
 }
 
-impl Clone for Storage {
+impl Clone for FileStorage {
     fn clone(&self) -> Self {
-        Storage {
+        FileStorage {
             base_dir: self.base_dir.clone(),
             index_file: self.index_file.clone(),
             index: Arc::clone(&self.index),
@@ -40,15 +88,22 @@ impl Clone for Storage {
     }
 }
 
-impl Storage {
+impl FileStorage {
     pub fn new() -> io::Result<Self> {
-        let base_dir = PathBuf::from(STORAGE_DIR);
+        Self::with_base_dir(STORAGE_DIR)
+    }
+
+    /// Like `new`, but persists under `base_dir` instead of the default
+    /// `cache_storage` directory. Kept private since the only caller outside
+    /// `new` is tests that need an isolated, disposable directory per case.
+    fn with_base_dir(base_dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let base_dir = base_dir.into();
         let index_file = base_dir.join(INDEX_FILE);
 
         // Create storage directory if it doesn't exist
         std::fs::create_dir_all(&base_dir)?;
 
-        let storage = Storage {
+        let storage = FileStorage {
             base_dir,
             index_file,
             index: Arc::new(Mutex::new(Vec::new())),
@@ -59,6 +114,11 @@ impl Storage {
             storage.load_index()?;
         }
 
+        // Bound the append-only logs from prior runs before serving traffic
+        if let Err(e) = storage.compact() {
+            error!("Startup compaction failed: {}", e);
+        }
+
         Ok(storage)
     }
 
@@ -75,17 +135,81 @@ impl Storage {
         Ok(())
     }
 
-    pub fn save(&self, key: &str, entry: &CacheEntry) -> io::Result<()> {
+    /// Persist the in-memory index to `index_file`. Without this, a restart
+    /// finds no index on disk and starts `index` empty even though the
+    /// `.cache` files are still there — `load` then has no offset to seek to
+    /// and silently returns each key's first-ever record instead of its
+    /// latest, and startup `compact()` finds no keys to compact at all.
+    fn save_index(&self) -> io::Result<()> {
+        let data = bincode::serialize(&*self.index.lock().unwrap())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&self.index_file)?;
+        file.write_all(&data)
+    }
+
+    fn file_path_for(&self, key: &str) -> PathBuf {
+        self.base_dir.join(format!("{}.cache", prefixed_key_hex(CacheKind::Entry, key)))
+    }
+
+    /// Rewrite each key's file to contain only its most recent record and
+    /// reset the index's offset/size bookkeeping accordingly, so the
+    /// append-only log doesn't grow without bound across repeated writes of
+    /// the same key. Safe to call on startup or on demand.
+    pub fn compact(&self) -> io::Result<()> {
+        let keys = StorageBackend::keys(self);
+        let mut compacted = 0;
+
+        for key in keys {
+            let entry = match StorageBackend::load(self, &key)? {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            let data = bincode::serialize(&entry)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let file_path = self.file_path_for(&key);
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&file_path)?;
+            file.write_all(&data)?;
+
+            let mut index = self.index.lock().unwrap();
+            if let Some(existing) = index.iter_mut().find(|e| e.key == key) {
+                existing.metadata.offset = 0;
+                existing.metadata.size = data.len() as u64;
+                existing.metadata.updated_at = Utc::now();
+            }
+            drop(index);
+            compacted += 1;
+        }
+
+        if compacted > 0 {
+            self.save_index()?;
+        }
+
+        info!("Compacted {} on-disk entr(ies)", compacted);
+        Ok(())
+    }
+}
+
+impl StorageBackend for FileStorage {
+    fn save(&self, key: &str, entry: &CacheEntry) -> io::Result<()> {
         debug!("Starting save operation for key: {}", key);
 
-        let file_path = self.base_dir.join(format!("{}.cache", key));
+        let file_path = self.file_path_for(key);
         debug!("Resolved file path for key: {}: {:?}", key, file_path);
 
-        let metadata = StorageMetadata {
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            size: 0, // Will be updated after writing
-        };
+        // This record starts at the file's current size; everything before
+        // this record's bytes is a stale write of this key's prior value(s).
+        let offset = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
 
         // Serialize the entry
         let data = match bincode::serialize(entry) {
@@ -141,19 +265,200 @@ impl Storage {
         let mut index = self.index.lock().unwrap();
         if let Some(existing) = index.iter_mut().find(|e| e.key == key) {
             existing.metadata.updated_at = Utc::now();
+            existing.metadata.offset = offset;
             existing.metadata.size = size;
         } else {
             index.push(IndexEntry {
                 key: key.to_string(),
                 file_path: file_path.to_string_lossy().into_owned(),
-                metadata,
+                metadata: StorageMetadata {
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                    offset,
+                    size,
+                },
             });
         }
 
         // Save the updated index
         debug!("Saving updated index for key: {}", key);
         debug!("Index size after update: {}", index.len());
+        drop(index);
+        self.save_index()?;
         info!("Successfully completed save operation for key: {}", key);
         Ok(())
     }
+
+    /// Read back a previously-`save`d entry, e.g. to repopulate the
+    /// in-memory cache after it was evicted there but is still on disk.
+    /// Returns `Ok(None)` if `key` was never saved. The file is append-only,
+    /// so this seeks to the offset of the latest record (tracked in the
+    /// index) rather than reading from the start, which would return a
+    /// stale, since-overwritten value.
+    fn load(&self, key: &str) -> io::Result<Option<CacheEntry>> {
+        let file_path = self.file_path_for(key);
+        if !file_path.exists() {
+            debug!("No on-disk entry for key: {}", key);
+            return Ok(None);
+        }
+
+        let offset = self
+            .index
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|e| e.key == key)
+            .map(|e| e.metadata.offset);
+
+        let mut file = File::open(&file_path)?;
+        if let Some(offset) = offset {
+            file.seek(SeekFrom::Start(offset))?;
+        }
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        if contents.is_empty() {
+            return Ok(None);
+        }
+
+        let entry = bincode::deserialize(&contents)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Some(entry))
+    }
+
+    /// Remove a previously-`save`d entry from disk and the index. Now that
+    /// `FastbuCache::get` can reload evicted entries from disk, a removed key
+    /// has to be cleared here too, or it would reappear on the next lookup.
+    fn delete(&self, key: &str) -> io::Result<bool> {
+        let file_path = self.file_path_for(key);
+        let existed = file_path.exists();
+        if existed {
+            std::fs::remove_file(&file_path)?;
+        }
+
+        let mut index = self.index.lock().unwrap();
+        index.retain(|e| e.key != key);
+        drop(index);
+        self.save_index()?;
+        Ok(existed)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.index.lock().unwrap().iter().map(|e| e.key.clone()).collect()
+    }
+}
+
+/// Embedded-RocksDB-backed `StorageBackend`: durable, crash-consistent
+/// persistence without the append-only growth of `FileStorage`, at the cost
+/// of an extra native dependency.
+pub struct RocksDbStorage {
+    db: rocksdb::DB,
+}
+
+impl RocksDbStorage {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let db = rocksdb::DB::open_default(&path).map_err(|e| {
+            io::Error::other(format!("failed to open rocksdb at {:?}: {}", path.as_ref(), e))
+        })?;
+        Ok(RocksDbStorage { db })
+    }
+}
+
+impl StorageBackend for RocksDbStorage {
+    fn save(&self, key: &str, entry: &CacheEntry) -> io::Result<()> {
+        let data = bincode::serialize(entry)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.db
+            .put(prefixed_key(CacheKind::Entry, key), data)
+            .map_err(|e| io::Error::other(format!("rocksdb put failed for key {}: {}", key, e)))
+    }
+
+    fn load(&self, key: &str) -> io::Result<Option<CacheEntry>> {
+        let bytes = self
+            .db
+            .get(prefixed_key(CacheKind::Entry, key))
+            .map_err(|e| io::Error::other(format!("rocksdb get failed for key {}: {}", key, e)))?;
+
+        match bytes {
+            Some(bytes) => {
+                let entry = bincode::deserialize(&bytes)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                Ok(Some(entry))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn delete(&self, key: &str) -> io::Result<bool> {
+        let prefixed = prefixed_key(CacheKind::Entry, key);
+        let existed = self
+            .db
+            .get(&prefixed)
+            .map_err(|e| io::Error::other(format!("rocksdb get failed for key {}: {}", key, e)))?
+            .is_some();
+
+        self.db
+            .delete(&prefixed)
+            .map_err(|e| io::Error::other(format!("rocksdb delete failed for key {}: {}", key, e)))?;
+        Ok(existed)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let prefix = [CacheKind::Entry as u8];
+        self.db
+            .prefix_iterator(prefix)
+            .filter_map(|res| {
+                let (key, _value) = res.ok()?;
+                if key.first() != Some(&(CacheKind::Entry as u8)) {
+                    return None;
+                }
+                String::from_utf8(key[1..].to_vec()).ok()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_storage_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("fastbu-storage-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_load_returns_latest_value_after_restart() {
+        let dir = temp_storage_dir();
+
+        {
+            let storage = FileStorage::with_base_dir(&dir).unwrap();
+            storage.save("k", &CacheEntry::new("first".to_string(), None)).unwrap();
+            storage.save("k", &CacheEntry::new("second".to_string(), None)).unwrap();
+            assert_eq!(storage.load("k").unwrap().unwrap().value(), "second");
+        }
+
+        // A fresh instance over the same directory simulates a restart: the
+        // index must have been persisted to disk, or this falls back to
+        // reading from byte 0 and returns "first" instead of "second".
+        let restarted = FileStorage::with_base_dir(&dir).unwrap();
+        assert_eq!(restarted.load("k").unwrap().unwrap().value(), "second");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_delete_persists_across_restart() {
+        let dir = temp_storage_dir();
+
+        {
+            let storage = FileStorage::with_base_dir(&dir).unwrap();
+            storage.save("k", &CacheEntry::new("v".to_string(), None)).unwrap();
+            assert!(storage.delete("k").unwrap());
+        }
+
+        let restarted = FileStorage::with_base_dir(&dir).unwrap();
+        assert!(restarted.load("k").unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }